@@ -0,0 +1,221 @@
+//! Trackers that boards can be read from and moved on
+//!
+//! The app talks to every tracker through [`BoardBackend`]. GitHub is the
+//! default backend; other backends are registered under a prefix and own the
+//! organization and project IDs of the form `<prefix>:<rest>`. GitHub logins
+//! and node IDs never contain `:`, so these can't be mistaken for GitHub ones.
+
+use crate::github::{
+    GitHubClient, ItemFieldState, Organization, Project, ProjectData, ProjectItem,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use std::sync::Arc;
+
+/// A tracker holding project boards
+///
+/// Listing, fetching and moving are required. The other mutations are
+/// optional and fail by default.
+#[async_trait]
+pub trait BoardBackend: Send + Sync {
+    /// Name of the tracker, used in logs and errors
+    fn name(&self) -> &str;
+
+    /// Check whether the tracker can be reached right now, without retries
+    async fn is_reachable(&self) -> bool {
+        true
+    }
+
+    /// List the organizations or groups that own boards
+    async fn list_organizations(&self) -> Result<Vec<Organization>>;
+
+    /// List the boards of an organization
+    async fn list_projects(&self, org: &str) -> Result<Vec<Project>>;
+
+    /// Fetch a board with all its columns and items
+    async fn project_data(&self, project_id: &str) -> Result<ProjectData>;
+
+    /// Fetch a board again, reusing `previous` where the tracker allows it
+    async fn refresh_project_data(&self, previous: &ProjectData) -> Result<ProjectData> {
+        self.project_data(&previous.project.id).await
+    }
+
+    /// Get the option an item currently has for a field, plus its version
+    async fn item_field_state(&self, item_id: &str, field_id: &str) -> Result<ItemFieldState>;
+
+    /// Move an item to another column, returning its new version
+    async fn move_item(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        field_id: &str,
+        column_id: &str,
+    ) -> Result<Option<String>>;
+
+    /// Add a card to a column, returning it
+    async fn create_item(
+        &self,
+        _project_id: &str,
+        _column_id: &str,
+        _title: &str,
+    ) -> Result<ProjectItem> {
+        anyhow::bail!("{} boards do not support adding cards", self.name())
+    }
+
+    /// Delete a card from its board
+    async fn delete_item(&self, _project_id: &str, _item_id: &str) -> Result<()> {
+        anyhow::bail!("{} boards do not support deleting cards", self.name())
+    }
+
+    /// Set a single-select field of an item, or clear it
+    async fn set_item_field(
+        &self,
+        _project_id: &str,
+        _item_id: &str,
+        _field_id: &str,
+        _option_id: Option<&str>,
+    ) -> Result<()> {
+        anyhow::bail!("{} boards do not support editing fields", self.name())
+    }
+
+    /// Get the ID of the issue/PR behind an item
+    async fn item_content_id(&self, _item_id: &str) -> Result<String> {
+        anyhow::bail!("{} boards do not support assignments", self.name())
+    }
+
+    /// Assign a user to an item's issue/PR, or remove their assignment
+    async fn set_assigned(&self, _content_id: &str, _login: &str, _assigned: bool) -> Result<()> {
+        anyhow::bail!("{} boards do not support assignments", self.name())
+    }
+
+    /// Archive or unarchive an item
+    async fn set_item_archived(
+        &self,
+        _project_id: &str,
+        _item_id: &str,
+        _archived: bool,
+    ) -> Result<()> {
+        anyhow::bail!("{} boards do not support archiving", self.name())
+    }
+}
+
+/// Creates a backend, called for every request so credentials are always current
+pub type Connect = Arc<dyn Fn() -> Result<Arc<dyn BoardBackend>> + Send + Sync>;
+
+/// A backend or why it could not connect, with the prefix it owns (`None` for the default one)
+pub type Connection<'a> = (Option<&'a str>, Result<Arc<dyn BoardBackend>>);
+
+/// Backends by the prefix of the IDs they own
+pub struct Backends {
+    registered: Vec<(String, Connect)>,
+    fallback: Connect,
+}
+
+impl Backends {
+    /// Create a registry where every unprefixed ID belongs to `fallback`
+    pub fn new(fallback: Connect) -> Self {
+        Self {
+            registered: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Create a registry with GitHub as the default backend
+    pub fn github() -> Self {
+        Self::new(Arc::new(|| Ok(Arc::new(GitHubClient::new()?))))
+    }
+
+    /// Hand organization and project IDs of the form `<prefix>:<rest>` to another backend
+    pub fn register(&mut self, prefix: &str, connect: Connect) {
+        debug!("Registering board backend for {prefix}:");
+        self.registered.push((prefix.to_string(), connect));
+    }
+
+    /// Get the prefix of the backend owning an organization or project ID, `None` for the default one
+    pub fn prefix_of(&self, id: &str) -> Option<&str> {
+        self.registered
+            .iter()
+            .map(|(prefix, _)| prefix.as_str())
+            .find(|prefix| {
+                id.strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_prefix(':'))
+                    .is_some_and(|rest| !rest.is_empty())
+            })
+    }
+
+    /// Get the backend owning an organization or project ID
+    pub fn for_id(&self, id: &str) -> Result<Arc<dyn BoardBackend>> {
+        let connect = self
+            .prefix_of(id)
+            .and_then(|prefix| self.registered.iter().find(|(p, _)| p == prefix))
+            .map_or(&self.fallback, |(_, connect)| connect);
+        connect()
+    }
+
+    /// Connect every backend, the default one first
+    ///
+    /// A backend that cannot connect, because it is not configured or has no
+    /// credentials, is returned as an error so callers can skip it on its own.
+    pub fn connect_all(&self) -> Vec<Connection<'_>> {
+        std::iter::once((None, &self.fallback))
+            .chain(
+                self.registered
+                    .iter()
+                    .map(|(prefix, connect)| (Some(prefix.as_str()), connect)),
+            )
+            .map(|(prefix, connect)| (prefix, connect()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A backend that fails to connect with its own name
+    fn failing(name: &'static str) -> Connect {
+        Arc::new(move || -> Result<Arc<dyn BoardBackend>> { anyhow::bail!("{name}") })
+    }
+
+    fn backends() -> Backends {
+        let mut backends = Backends::new(failing("github"));
+        for prefix in ["local", "gitlab", "gitea"] {
+            backends.register(prefix, failing(prefix));
+        }
+        backends
+    }
+
+    fn owner(backends: &Backends, id: &str) -> String {
+        match backends.for_id(id) {
+            Ok(backend) => backend.name().to_string(),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn prefixed_ids_go_to_their_backend() {
+        let backends = backends();
+        assert_eq!(owner(&backends, "gitlab:group/12/34"), "gitlab");
+        assert_eq!(owner(&backends, "gitlab:my-group"), "gitlab");
+        assert_eq!(owner(&backends, "gitea:owner/7"), "gitea");
+        assert_eq!(owner(&backends, "local:/home/me/board.md"), "local");
+        assert_eq!(backends.prefix_of("local:boards"), Some("local"));
+    }
+
+    #[test]
+    fn github_logins_named_like_a_prefix_stay_on_github() {
+        let backends = backends();
+        for id in [
+            "gitlab",
+            "gitea",
+            "local",
+            "gitlab:",
+            "gitlabhq",
+            "PVT_kwDOABC",
+        ] {
+            assert_eq!(backends.prefix_of(id), None, "{id}");
+            assert_eq!(owner(&backends, id), "github", "{id}");
+        }
+    }
+}
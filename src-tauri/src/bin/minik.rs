@@ -0,0 +1,281 @@
+//! Command-line companion to the minik app
+//!
+//! Uses the same `gh` authentication and GitHub client as the app, so boards
+//! can be read and updated from terminals, scripts and git hooks.
+
+use anyhow::{Context as _, Result};
+use clap::{Parser, Subcommand};
+use minik_app_lib::config::{self, ConfigStore};
+use minik_app_lib::github::{self, GitHubClient, Project, ProjectData, ProjectItem};
+use minik_app_lib::mcp;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "minik",
+    version,
+    about = "Read and update GitHub project boards"
+)]
+struct Cli {
+    /// Print JSON instead of a table
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Use this account when gh is logged in to several
+    #[arg(long, global = true, value_name = "LOGIN")]
+    account: Option<String>,
+
+    /// Read config.toml from this directory
+    #[arg(long, global = true, value_name = "DIR")]
+    config_dir: Option<PathBuf>,
+
+    /// Log requests to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the project boards of every organization you belong to
+    Boards,
+    /// Show the items of a board by column
+    Show {
+        /// Project as <org>/<number> or project ID, defaults to default_project in config.toml
+        project: Option<String>,
+    },
+    /// Move an item to another column
+    Move {
+        /// Item ID, issue or PR URL, or #number
+        item: String,
+        /// Column name or ID
+        column: String,
+        /// Project as <org>/<number> or project ID, defaults to default_project in config.toml
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Serve the boards to AI assistants over the Model Context Protocol on stdio
+    Mcp {
+        /// Also offer the tools that move items and add comments
+        #[arg(long)]
+        allow_writes: bool,
+    },
+    /// List the items assigned to you
+    Mine {
+        /// Project as <org>/<number> or project ID, defaults to every board
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+/// A board as listed by `minik boards`
+#[derive(Serialize)]
+struct BoardRow {
+    org: String,
+    #[serde(flatten)]
+    project: Project,
+}
+
+/// An item with the names of its board and column
+#[derive(Serialize)]
+struct ItemRow {
+    project: String,
+    column: String,
+    #[serde(flatten)]
+    item: ProjectItem,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if cli.verbose {
+        env_logger::Builder::new()
+            .filter_level(log::LevelFilter::Debug)
+            .init();
+    }
+    if let Some(dir) = cli.config_dir.clone() {
+        config::set_config_dir(dir);
+    }
+    if let Some(account) = cli.account.clone() {
+        github::set_account(account);
+    }
+
+    if let Err(e) = run(cli).await {
+        eprintln!("minik: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let client = GitHubClient::new()?;
+
+    match cli.command {
+        Command::Boards => {
+            let mut boards = Vec::new();
+            for org in client.list_organizations().await? {
+                for project in client.list_org_projects(&org.login).await? {
+                    boards.push(BoardRow {
+                        org: org.login.clone(),
+                        project,
+                    });
+                }
+            }
+            print_rows(cli.json, &boards, &["ORG", "NUMBER", "TITLE", "ID"], |b| {
+                vec![
+                    b.org.clone(),
+                    b.project.number.to_string(),
+                    b.project.title.clone(),
+                    b.project.id.clone(),
+                ]
+            })
+        }
+        Command::Show { project } => {
+            let data = client
+                .project_data(&resolve_project(&client, project).await?)
+                .await?;
+            let rows = item_rows(&data, |_| true);
+            print_items(cli.json, &rows)
+        }
+        Command::Move {
+            item,
+            column,
+            project,
+        } => {
+            let data = client
+                .project_data(&resolve_project(&client, project).await?)
+                .await?;
+            let item = data.find_item(&item)?;
+            let column = data.find_column(&column)?;
+
+            client
+                .update_item_field(
+                    &data.project.id,
+                    &item.id,
+                    &data.status_field_id,
+                    &column.id,
+                )
+                .await?;
+
+            let moved = ItemRow {
+                project: data.project.title.clone(),
+                column: column.name.clone(),
+                item: ProjectItem {
+                    column_id: column.id.clone(),
+                    ..item.clone()
+                },
+            };
+            print_items(cli.json, &[moved])
+        }
+        Command::Mcp { allow_writes } => mcp::Server::new(client, allow_writes).serve_stdio().await,
+        Command::Mine { project } => {
+            let login = match github::account() {
+                Some(account) => account.to_string(),
+                None => client.viewer_login().await?,
+            };
+            let project_ids = match project {
+                Some(project) => vec![resolve_project(&client, Some(project)).await?],
+                None => {
+                    let mut ids = Vec::new();
+                    for org in client.list_organizations().await? {
+                        ids.extend(
+                            client
+                                .list_org_projects(&org.login)
+                                .await?
+                                .into_iter()
+                                .map(|p| p.id),
+                        );
+                    }
+                    ids
+                }
+            };
+
+            let mut rows = Vec::new();
+            for project_id in project_ids {
+                let data = client.project_data(&project_id).await?;
+                rows.extend(item_rows(&data, |item| item.assignees.contains(&login)));
+            }
+            print_items(cli.json, &rows)
+        }
+    }
+}
+
+/// Find the ID of a project given as `org/number` or project ID, falling back to the config
+async fn resolve_project(client: &GitHubClient, project: Option<String>) -> Result<String> {
+    let project = project
+        .or_else(|| ConfigStore::load().get().default_project)
+        .context("No project given and no default_project in config.toml")?;
+    client.resolve_project(&project).await
+}
+
+/// Get the items of a board that match a filter, in board order
+fn item_rows(data: &ProjectData, keep: impl Fn(&ProjectItem) -> bool) -> Vec<ItemRow> {
+    data.columns
+        .iter()
+        .flat_map(|column| {
+            data.items
+                .iter()
+                .filter(|item| item.column_id == column.id && keep(item))
+                .map(|item| ItemRow {
+                    project: data.project.title.clone(),
+                    column: column.name.clone(),
+                    item: item.clone(),
+                })
+        })
+        .collect()
+}
+
+fn print_items(json: bool, rows: &[ItemRow]) -> Result<()> {
+    print_rows(
+        json,
+        rows,
+        &["PROJECT", "COLUMN", "TITLE", "ASSIGNEES", "URL"],
+        |r| {
+            vec![
+                r.project.clone(),
+                r.column.clone(),
+                r.item.title.clone(),
+                r.item.assignees.join(","),
+                r.item.url.clone().unwrap_or_else(|| r.item.id.clone()),
+            ]
+        },
+    )
+}
+
+/// Print rows as pretty JSON or as a table with aligned columns
+fn print_rows<T: Serialize>(
+    json: bool,
+    rows: &[T],
+    headers: &[&str],
+    cells: impl Fn(&T) -> Vec<String>,
+) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(rows)?);
+        return Ok(());
+    }
+
+    let table: Vec<Vec<String>> = std::iter::once(headers.iter().map(|h| h.to_string()).collect())
+        .chain(rows.iter().map(cells))
+        .collect();
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            table
+                .iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in &table {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+    Ok(())
+}
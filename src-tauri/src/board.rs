@@ -0,0 +1,323 @@
+//! Backend-side knowledge of the boards shown in the UI
+//!
+//! Owns the current data of every board being shown, computes what changed
+//! between refreshes, and tracks the last known column and version of every
+//! item so moves can be applied optimistically and checked against GitHub.
+
+use crate::github::{ProjectData, ProjectItem};
+use log::{debug, error, info};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often a board is fetched in full instead of incrementally, to catch
+/// deleted items and column changes that `updatedAt` does not reveal
+pub const FULL_RESYNC_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Change to a single item between two refreshes of a board
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ItemChange {
+    /// Item appeared on the board
+    Added { item: ProjectItem },
+    /// Item moved to another column, possibly with other changes
+    Moved {
+        item: ProjectItem,
+        from_column_id: String,
+    },
+    /// Item title, assignees, labels or URL changed
+    Updated { item: ProjectItem },
+    /// Item disappeared from the board
+    Removed { item_id: String },
+}
+
+impl ItemChange {
+    /// Get the name of the event announcing this change to the UI
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Added { .. } => "item-added",
+            Self::Moved { .. } => "item-moved",
+            Self::Updated { .. } => "item-updated",
+            Self::Removed { .. } => "item-removed",
+        }
+    }
+}
+
+/// Compute the item changes between two versions of a board
+pub fn diff_items(old: &[ProjectItem], new: &[ProjectItem]) -> Vec<ItemChange> {
+    let old_by_id: HashMap<&str, &ProjectItem> =
+        old.iter().map(|item| (item.id.as_str(), item)).collect();
+    let new_by_id: HashMap<&str, &ProjectItem> =
+        new.iter().map(|item| (item.id.as_str(), item)).collect();
+
+    let mut changes = Vec::new();
+    for item in new {
+        match old_by_id.get(item.id.as_str()) {
+            None => changes.push(ItemChange::Added { item: item.clone() }),
+            Some(previous) if previous.column_id != item.column_id => {
+                changes.push(ItemChange::Moved {
+                    item: item.clone(),
+                    from_column_id: previous.column_id.clone(),
+                });
+            }
+            Some(previous)
+                if previous.title != item.title
+                    || previous.assignees != item.assignees
+                    || previous.labels != item.labels
+                    || previous.url != item.url =>
+            {
+                changes.push(ItemChange::Updated { item: item.clone() });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for item in old {
+        if !new_by_id.contains_key(item.id.as_str()) {
+            changes.push(ItemChange::Removed {
+                item_id: item.id.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// What the UI needs to hear after a board was stored
+pub enum BoardUpdate {
+    /// Board is new or its columns changed, so it has to be redrawn entirely
+    Replaced(ProjectData),
+    /// Only these items changed
+    Items(Vec<ItemChange>),
+}
+
+/// Thread-safe map of project ID to the current board data
+#[derive(Default)]
+pub struct BoardStore {
+    boards: Mutex<HashMap<String, ProjectData>>,
+    full_syncs: Mutex<HashMap<String, Instant>>,
+}
+
+impl BoardStore {
+    /// Get the current data of a board
+    pub fn get(&self, project_id: &str) -> Option<ProjectData> {
+        self.boards.lock().ok()?.get(project_id).cloned()
+    }
+
+    /// Get the ID of the field whose options are a board's columns
+    pub fn status_field_id(&self, project_id: &str) -> Option<String> {
+        self.boards
+            .lock()
+            .ok()?
+            .get(project_id)
+            .map(|data| data.status_field_id.clone())
+            .filter(|id| !id.is_empty())
+    }
+
+    /// Check whether a board is due for a full fetch rather than an incremental one
+    pub fn needs_full_sync(&self, project_id: &str) -> bool {
+        self.full_syncs
+            .lock()
+            .ok()
+            .and_then(|syncs| syncs.get(project_id).copied())
+            .is_none_or(|at| at.elapsed() >= FULL_RESYNC_INTERVAL)
+    }
+
+    /// Store a freshly fetched board and work out what changed
+    ///
+    /// `full_sync` tells whether the board was fetched in full, restarting the
+    /// countdown to the next full resync.
+    pub fn update(&self, data: ProjectData, full_sync: bool) -> BoardUpdate {
+        if full_sync {
+            if let Ok(mut syncs) = self.full_syncs.lock() {
+                syncs.insert(data.project.id.clone(), Instant::now());
+            }
+        }
+
+        let mut boards = match self.boards.lock() {
+            Ok(boards) => boards,
+            Err(e) => {
+                error!("Failed to lock board store: {e}");
+                return BoardUpdate::Replaced(data);
+            }
+        };
+
+        let update = match boards.get(&data.project.id) {
+            Some(previous) if same_columns(previous, &data) => {
+                BoardUpdate::Items(diff_items(&previous.items, &data.items))
+            }
+            _ => BoardUpdate::Replaced(data.clone()),
+        };
+
+        if let BoardUpdate::Items(changes) = &update {
+            info!(
+                "Board {} refreshed with {} item changes",
+                data.project.id,
+                changes.len()
+            );
+        }
+        boards.insert(data.project.id.clone(), data);
+        update
+    }
+
+    /// Record a move made by minik itself so it is not reported as a remote change
+    pub fn move_item(&self, project_id: &str, item_id: &str, column_id: &str) {
+        match self.boards.lock() {
+            Ok(mut boards) => {
+                if let Some(item) = boards
+                    .get_mut(project_id)
+                    .and_then(|board| board.items.iter_mut().find(|i| i.id == item_id))
+                {
+                    item.column_id = column_id.to_string();
+                }
+            }
+            Err(e) => error!("Failed to lock board store: {e}"),
+        }
+    }
+}
+
+/// Check whether two versions of a board have the same columns in the same order
+fn same_columns(a: &ProjectData, b: &ProjectData) -> bool {
+    a.columns.len() == b.columns.len()
+        && a.columns
+            .iter()
+            .zip(&b.columns)
+            .all(|(x, y)| x.id == y.id && x.name == y.name)
+}
+
+/// Last known status of a project item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownItem {
+    /// Column (status option) the item was last seen in
+    pub column_id: String,
+    /// Item `updatedAt` when it was last seen
+    pub updated_at: Option<String>,
+}
+
+/// Thread-safe map of item ID to its last known status
+#[derive(Default)]
+pub struct KnownItems(Mutex<HashMap<String, KnownItem>>);
+
+impl KnownItems {
+    /// Remember the status of every item on a freshly fetched board
+    pub fn record_board(&self, data: &ProjectData) {
+        match self.0.lock() {
+            Ok(mut known) => {
+                for item in &data.items {
+                    known.insert(
+                        item.id.clone(),
+                        KnownItem {
+                            column_id: item.column_id.clone(),
+                            updated_at: item.updated_at.clone(),
+                        },
+                    );
+                }
+                debug!("Recorded {} known items", data.items.len());
+            }
+            Err(e) => error!("Failed to lock known items: {e}"),
+        }
+    }
+
+    /// Get the last known status of an item
+    pub fn get(&self, item_id: &str) -> Option<KnownItem> {
+        self.0.lock().ok()?.get(item_id).cloned()
+    }
+
+    /// Update the last known status of an item
+    pub fn set(&self, item_id: &str, item: KnownItem) {
+        match self.0.lock() {
+            Ok(mut known) => {
+                known.insert(item_id.to_string(), item);
+            }
+            Err(e) => error!("Failed to lock known items: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, title: &str, column_id: &str) -> ProjectItem {
+        ProjectItem {
+            id: id.to_string(),
+            title: title.to_string(),
+            assignees: Vec::new(),
+            column_id: column_id.to_string(),
+            labels: Vec::new(),
+            url: None,
+            updated_at: None,
+            content_id: None,
+        }
+    }
+
+    #[test]
+    fn an_unchanged_board_has_no_changes() {
+        let items = [item("1", "First", "todo"), item("2", "Second", "done")];
+        assert!(diff_items(&items, &items).is_empty());
+    }
+
+    #[test]
+    fn reports_added_moved_updated_and_removed_items() {
+        let old = [
+            item("moved", "Moved", "todo"),
+            item("renamed", "Old title", "todo"),
+            item("removed", "Removed", "done"),
+            item("same", "Same", "done"),
+        ];
+        let new = [
+            item("moved", "Moved and renamed", "done"),
+            item("renamed", "New title", "todo"),
+            item("same", "Same", "done"),
+            item("added", "Added", "todo"),
+        ];
+
+        let changes = diff_items(&old, &new);
+
+        let summary: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|change| match change {
+                ItemChange::Added { item }
+                | ItemChange::Moved { item, .. }
+                | ItemChange::Updated { item } => (change.event_name(), item.id.as_str()),
+                ItemChange::Removed { item_id } => (change.event_name(), item_id.as_str()),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("item-moved", "moved"),
+                ("item-updated", "renamed"),
+                ("item-added", "added"),
+                ("item-removed", "removed"),
+            ]
+        );
+        let ItemChange::Moved {
+            item,
+            from_column_id,
+        } = &changes[0]
+        else {
+            panic!("expected a move, got {:?}", changes[0]);
+        };
+        assert_eq!(from_column_id, "todo");
+        assert_eq!(item.title, "Moved and renamed");
+    }
+
+    #[test]
+    fn assignee_label_and_url_changes_are_updates() {
+        let old = [item("1", "Task", "todo")];
+        let mut assigned = item("1", "Task", "todo");
+        assigned.assignees = vec!["octocat".to_string()];
+        let mut labelled = item("1", "Task", "todo");
+        labelled.labels = vec!["bug".to_string()];
+        let mut linked = item("1", "Task", "todo");
+        linked.url = Some("https://github.com/org/repo/issues/1".to_string());
+
+        for new in [assigned, labelled, linked] {
+            let changes = diff_items(&old, std::slice::from_ref(&new));
+            assert_eq!(changes.len(), 1, "{new:?}");
+            assert_eq!(changes[0].event_name(), "item-updated", "{new:?}");
+        }
+    }
+}
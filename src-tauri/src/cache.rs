@@ -0,0 +1,85 @@
+//! On-disk cache of the last successfully fetched board for each project
+//!
+//! Boards are stored as JSON files in a `boards` directory next to `state.json`,
+//! so the sticky window can show something useful on startup or while offline.
+
+use crate::github::ProjectData;
+use log::{debug, error, info, warn};
+use std::path::PathBuf;
+
+/// Get the directory holding cached boards
+fn cache_directory() -> Option<PathBuf> {
+    crate::config::config_dir().map(|p| p.join("boards"))
+}
+
+/// Get the cache file path for a project
+fn cache_path(project_id: &str) -> Option<PathBuf> {
+    // Node IDs are already filename-safe, but never trust them with a path
+    let file_name: String = project_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    cache_directory().map(|dir| dir.join(format!("{file_name}.json")))
+}
+
+/// Persist a freshly fetched board
+pub fn store(data: &ProjectData) {
+    let Some(path) = cache_path(&data.project.id) else {
+        error!("Could not determine board cache directory");
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            error!("Failed to create board cache directory: {e}");
+            return;
+        }
+    }
+
+    match serde_json::to_string(data) {
+        Ok(json) => match crate::state::write_atomic(&path, &json) {
+            Ok(()) => debug!("Cached board for project {} to {path:?}", data.project.id),
+            Err(e) => error!("Failed to write board cache {path:?}: {e}"),
+        },
+        Err(e) => error!("Failed to serialize board cache: {e}"),
+    }
+}
+
+/// Load the last cached board for a project, marked as stale
+pub fn load(project_id: &str) -> Option<ProjectData> {
+    let path = cache_path(project_id)?;
+
+    let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                debug!("No cached board for project {project_id}");
+            } else {
+                warn!("Failed to read board cache {path:?}: {e}");
+            }
+            return None;
+        }
+    };
+
+    match serde_json::from_str::<ProjectData>(&json) {
+        Ok(mut data) => {
+            data.stale = true;
+            info!(
+                "Loaded cached board for project {project_id} (fetched at {:?})",
+                data.fetched_at
+            );
+            Some(data)
+        }
+        Err(e) => {
+            warn!("Failed to parse board cache {path:?}: {e}");
+            None
+        }
+    }
+}
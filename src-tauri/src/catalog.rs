@@ -0,0 +1,221 @@
+//! Persisted catalog of the organizations and projects the user can pick from
+//!
+//! Listing projects takes one request per organization, so the catalog is kept
+//! in `catalog.json` and only refreshed once it is older than [`CATALOG_TTL`]
+//! or when the user asks for it.
+
+use crate::backend::Backends;
+use crate::github::{Organization, Project};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long a catalog is used before it is refreshed in the background
+pub const CATALOG_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Organizations and their projects as last fetched from the board backends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    /// Organizations the user belongs to
+    pub organizations: Vec<Organization>,
+    /// Organization login -> projects in that organization
+    pub projects: BTreeMap<String, Vec<Project>>,
+    /// When the catalog was fetched
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl Catalog {
+    /// Fetch the catalog from every board backend
+    ///
+    /// Backends that cannot connect or list their organizations, and
+    /// organizations whose projects cannot be listed, keep their entries from
+    /// `previous`, so one failing tracker or org does not empty the menu.
+    pub async fn fetch(backends: &Backends, previous: Option<&Catalog>) -> Result<Self> {
+        let fetched_at = Utc::now();
+        let mut organizations = Vec::new();
+        let mut owners = Vec::new();
+        let mut projects = BTreeMap::new();
+        let mut errors = Vec::new();
+        for (prefix, backend) in backends.connect_all() {
+            let listed = match backend {
+                Ok(backend) => backend
+                    .list_organizations()
+                    .await
+                    .map(|orgs| (backend, orgs)),
+                Err(e) => Err(e),
+            };
+            match listed {
+                Ok((backend, orgs)) => {
+                    for org in orgs {
+                        owners.push((org.login.clone(), backend.clone()));
+                        organizations.push(org);
+                    }
+                }
+                Err(e) => {
+                    let name = prefix.unwrap_or("GitHub");
+                    warn!("Skipping {name} organizations: {e}");
+                    errors.push(format!("{name}: {e}"));
+                    let Some(previous) = previous else {
+                        continue;
+                    };
+                    for org in &previous.organizations {
+                        if backends.prefix_of(&org.login) == prefix {
+                            if let Some(known) = previous.projects.get(&org.login) {
+                                projects.insert(org.login.clone(), known.clone());
+                            }
+                            organizations.push(org.clone());
+                        }
+                    }
+                }
+            }
+        }
+        if owners.is_empty() && organizations.is_empty() && !errors.is_empty() {
+            anyhow::bail!("No board backend could be listed: {}", errors.join("; "));
+        }
+
+        let results = join_all(owners.iter().map(|(login, backend)| async move {
+            (login.clone(), backend.list_projects(login).await)
+        }))
+        .await;
+
+        for (login, result) in results {
+            match result {
+                Ok(org_projects) => {
+                    projects.insert(login, org_projects);
+                }
+                Err(e) => {
+                    warn!("Failed to list projects for org {login}: {e}");
+                    if let Some(known) = previous.and_then(|c| c.projects.get(&login)) {
+                        projects.insert(login, known.clone());
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Fetched catalog: {} organizations, {} projects",
+            organizations.len(),
+            projects.values().map(Vec::len).sum::<usize>()
+        );
+
+        Ok(Self {
+            organizations,
+            projects,
+            fetched_at,
+        })
+    }
+
+    /// Check whether the catalog is older than [`CATALOG_TTL`]
+    pub fn is_expired(&self) -> bool {
+        (Utc::now() - self.fetched_at)
+            .to_std()
+            .is_ok_and(|age| age >= CATALOG_TTL)
+    }
+
+    /// Find the ID of a project given as `org/number`, or as a project ID
+    pub fn resolve(&self, project: &str) -> Option<String> {
+        let Some((org, number)) = project.split_once('/') else {
+            return Some(project.to_string());
+        };
+        let number: u64 = number.parse().ok()?;
+        self.projects
+            .iter()
+            .find(|(login, _)| login.eq_ignore_ascii_case(org))?
+            .1
+            .iter()
+            .find(|p| p.number == number)
+            .map(|p| p.id.clone())
+    }
+
+    /// Get the projects of every organization that has any
+    pub fn projects_by_org(&self) -> BTreeMap<String, Vec<Project>> {
+        self.projects
+            .iter()
+            .filter(|(_, projects)| !projects.is_empty())
+            .map(|(login, projects)| (login.clone(), projects.clone()))
+            .collect()
+    }
+}
+
+/// Thread-safe holder of the current catalog, persisted on every change
+pub struct CatalogStore(Mutex<Option<Catalog>>);
+
+impl CatalogStore {
+    /// Load the catalog saved by a previous session
+    pub fn load() -> Self {
+        Self(Mutex::new(load_catalog()))
+    }
+
+    /// Get the current catalog, however old
+    pub fn get(&self) -> Option<Catalog> {
+        self.0.lock().ok()?.clone()
+    }
+
+    /// Replace the catalog with a freshly fetched one
+    pub fn set(&self, catalog: Catalog) {
+        save_catalog(&catalog);
+        match self.0.lock() {
+            Ok(mut current) => *current = Some(catalog),
+            Err(e) => error!("Failed to lock catalog: {e}"),
+        }
+    }
+}
+
+/// Get the path of the catalog file
+fn catalog_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|p| p.join("catalog.json"))
+}
+
+fn save_catalog(catalog: &Catalog) {
+    let Some(path) = catalog_path() else {
+        error!("Could not determine config directory");
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            error!("Failed to create config directory: {e}");
+            return;
+        }
+    }
+
+    match serde_json::to_string(catalog) {
+        Ok(json) => match crate::state::write_atomic(&path, &json) {
+            Ok(()) => debug!("Saved catalog to {path:?}"),
+            Err(e) => error!("Failed to write catalog to {path:?}: {e}"),
+        },
+        Err(e) => error!("Failed to serialize catalog: {e}"),
+    }
+}
+
+fn load_catalog() -> Option<Catalog> {
+    let path = catalog_path()?;
+
+    match std::fs::read_to_string(&path) {
+        Ok(json) => match serde_json::from_str::<Catalog>(&json) {
+            Ok(catalog) => {
+                info!(
+                    "Loaded catalog fetched at {} from {path:?}",
+                    catalog.fetched_at
+                );
+                Some(catalog)
+            }
+            Err(e) => {
+                warn!("Failed to parse catalog: {e}");
+                None
+            }
+        },
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read catalog: {e}");
+            }
+            None
+        }
+    }
+}
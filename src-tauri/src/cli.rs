@@ -0,0 +1,135 @@
+//! Command-line arguments of the app
+//!
+//! Only one instance of minik runs at a time. Later launches hand their
+//! arguments to the running instance and exit, so `minik --project org/3`
+//! switches the board that is already on screen.
+
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::Parser;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Arguments accepted by the app
+#[derive(Parser, Debug, Default, Clone)]
+#[command(name = "minik", version, about = "A minimalist GitHub Kanban app")]
+pub struct Args {
+    /// Show this project instead of the last one
+    #[arg(long, value_name = "ORG/NUMBER", value_parser = parse_project)]
+    pub project: Option<String>,
+
+    /// Show the board expanded
+    #[arg(long)]
+    pub expanded: bool,
+
+    /// Keep config.toml, state and caches in this directory
+    #[arg(long, value_name = "DIR")]
+    pub config_dir: Option<PathBuf>,
+
+    /// Use this account when gh is logged in to several
+    #[arg(long, value_name = "LOGIN")]
+    pub account: Option<String>,
+
+    /// Write every GitHub request and response to this directory
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Answer GitHub requests from a recording instead of the network
+    #[arg(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// minik:// link the system opens the app with, handled by the deep link plugin
+    #[arg(value_name = "URL", hide = true)]
+    pub url: Option<String>,
+
+    /// Arguments that were not understood and left out
+    #[arg(skip)]
+    pub ignored: Vec<String>,
+}
+
+/// Check that a project is given as `<org>/<number>`
+fn parse_project(value: &str) -> Result<String, String> {
+    match value.split_once('/') {
+        Some((org, number)) if !org.is_empty() && number.parse::<u64>().is_ok() => {
+            Ok(value.to_string())
+        }
+        _ => Err(format!("expected <org>/<number>, got {value:?}")),
+    }
+}
+
+/// Parse the arguments of a launch, including the program name
+///
+/// Unknown arguments are skipped and listed in [`Args::ignored`] rather than
+/// failing, as launchers add their own, such as the `-psn_…` macOS passes to
+/// apps opened from Finder.
+pub fn parse<I, T>(args: I) -> Result<Args, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let mut ignored = Vec::new();
+    loop {
+        let error = match Args::try_parse_from(&args) {
+            Ok(parsed) => return Ok(Args { ignored, ..parsed }),
+            Err(e) if e.kind() == ErrorKind::UnknownArgument => e,
+            Err(e) => return Err(e),
+        };
+        let Some(ContextValue::String(unknown)) = error.get(ContextKind::InvalidArg) else {
+            return Err(error);
+        };
+        // Clap names only the flag of `--flag=value` and the first letter of `-abc`
+        let position = |matches: &dyn Fn(&str) -> bool| {
+            args.iter()
+                .skip(1)
+                .position(|arg| matches(&arg.to_string_lossy()))
+                .map(|index| index + 1)
+        };
+        let Some(index) = position(&|arg| arg == unknown)
+            .or_else(|| position(&|arg| arg.starts_with(unknown.as_str())))
+        else {
+            return Err(error);
+        };
+        ignored.push(args.remove(index).to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_must_be_org_and_number() {
+        let args = Args::try_parse_from(["minik", "--project", "codeGROOVE-dev/3"]).unwrap();
+        assert_eq!(args.project.as_deref(), Some("codeGROOVE-dev/3"));
+
+        for project in ["codeGROOVE-dev", "/3", "codeGROOVE-dev/three", "org/-3"] {
+            let error = Args::try_parse_from(["minik", "--project", project]).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::ValueValidation, "{project}");
+        }
+    }
+
+    #[test]
+    fn record_and_replay_conflict() {
+        let error = Args::try_parse_from(["minik", "--record", "a", "--replay", "b"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+        assert!(parse(["minik", "--record", "a", "--replay", "b"]).is_err());
+    }
+
+    #[test]
+    fn unknown_arguments_are_ignored() {
+        let args = parse([
+            "minik",
+            "-psn_0_12345",
+            "--expanded",
+            "--colour=always",
+            "--project",
+            "org/3",
+        ])
+        .unwrap();
+
+        assert_eq!(args.ignored, ["-psn_0_12345", "--colour=always"]);
+        assert!(args.expanded);
+        assert_eq!(args.project.as_deref(), Some("org/3"));
+        assert_eq!(args.url, None);
+    }
+}
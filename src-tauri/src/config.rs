@@ -0,0 +1,513 @@
+//! User-editable settings in `config.toml`
+//!
+//! The config file sits next to `state.json` but is never written by minik;
+//! the state file is machine-managed, this one belongs to the user. It is
+//! validated on load and reloaded whenever it changes on disk.
+
+use log::{debug, error, info, warn, LevelFilter};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Column colors the stylesheet has classes for
+pub const COLUMN_COLORS: &[&str] = &["yellow", "blue", "green", "pink", "orange", "purple"];
+
+/// Allowed range for the board refresh interval in seconds
+const REFRESH_INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 15..=3600;
+
+/// Allowed range for the column width in logical pixels
+const COLUMN_WIDTH_RANGE: std::ops::RangeInclusive<u32> = 100..=600;
+
+/// How densely cards are laid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+/// Sizes used to fit the window to the board
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Layout {
+    /// Width of a column in logical pixels
+    pub column_width: u32,
+    /// Gap between columns in logical pixels
+    pub column_gap: u32,
+    /// Total horizontal padding around the board in logical pixels
+    pub padding: u32,
+    pub card_density: CardDensity,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            column_width: 190,
+            column_gap: 4,
+            padding: 12,
+            card_density: CardDensity::Comfortable,
+        }
+    }
+}
+
+impl Layout {
+    /// Get the width needed to show a number of columns side by side
+    pub fn board_width(&self, column_count: u32) -> u32 {
+        self.padding
+            + self.column_width * column_count
+            + self.column_gap * column_count.saturating_sub(1)
+    }
+}
+
+/// Colors of the board
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    /// Colors given to columns from left to right, repeating when there are more columns
+    pub columns: Vec<String>,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self {
+            columns: COLUMN_COLORS.iter().map(|c| (*c).to_string()).collect(),
+        }
+    }
+}
+
+/// Connection to a GitLab instance whose issue boards are shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitLabConfig {
+    /// Base URL of the instance
+    #[serde(default = "GitLabConfig::default_url")]
+    pub url: String,
+    /// Personal access token with the `api` scope, `GITLAB_TOKEN` when missing
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Full paths of the groups to list, every group the token can see when empty
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl GitLabConfig {
+    fn default_url() -> String {
+        "https://gitlab.com".to_string()
+    }
+}
+
+/// Connection to a Gitea or Forgejo instance whose repositories are shown as boards
+///
+/// These are label boards standing in for project boards, which neither server
+/// offers through its API: columns come from the labels in `column_scope`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GiteaConfig {
+    /// Base URL of the instance
+    pub url: String,
+    /// Access token with read and write access to issues, `GITEA_TOKEN` when missing
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Users and organizations to list, the token's user and their organizations when empty
+    #[serde(default)]
+    pub owners: Vec<String>,
+    /// Scope of the labels that make columns, as in `status/In progress`
+    #[serde(default = "GiteaConfig::default_column_scope")]
+    pub column_scope: String,
+}
+
+impl GiteaConfig {
+    fn default_column_scope() -> String {
+        "status".to_string()
+    }
+}
+
+/// Settings read from `config.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// How often boards are refreshed in the background, in seconds
+    pub refresh_interval_secs: u64,
+    /// One of off, error, warn, info, debug or trace
+    pub log_level: String,
+    /// Project shown when a window has none selected, as `org/number` or a project ID
+    pub default_project: Option<String>,
+    /// Boards kept in `.json` or `.md` files, relative to the config directory unless absolute
+    pub local_boards: Vec<String>,
+    pub gitlab: Option<GitLabConfig>,
+    pub gitea: Option<GiteaConfig>,
+    pub layout: Layout,
+    pub colors: Colors,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            refresh_interval_secs: 90,
+            log_level: "info".to_string(),
+            default_project: None,
+            local_boards: Vec::new(),
+            gitlab: None,
+            gitea: None,
+            layout: Layout::default(),
+            colors: Colors::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Get the board refresh interval
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_interval_secs)
+    }
+
+    /// Get the configured log level
+    pub fn log_level(&self) -> LevelFilter {
+        LevelFilter::from_str(&self.log_level).unwrap_or(LevelFilter::Info)
+    }
+
+    /// Replace invalid values with their defaults, returning what was wrong
+    pub fn validate(&mut self) -> Vec<String> {
+        let defaults = Self::default();
+        let mut problems = Vec::new();
+
+        if !REFRESH_INTERVAL_RANGE.contains(&self.refresh_interval_secs) {
+            problems.push(format!(
+                "refresh_interval_secs must be between {} and {}, got {}",
+                REFRESH_INTERVAL_RANGE.start(),
+                REFRESH_INTERVAL_RANGE.end(),
+                self.refresh_interval_secs
+            ));
+            self.refresh_interval_secs = defaults.refresh_interval_secs;
+        }
+
+        if LevelFilter::from_str(&self.log_level).is_err() {
+            problems.push(format!(
+                "log_level must be one of off, error, warn, info, debug or trace, got {:?}",
+                self.log_level
+            ));
+            self.log_level = defaults.log_level;
+        }
+
+        if self
+            .default_project
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            problems.push("default_project must not be empty".to_string());
+            self.default_project = None;
+        }
+
+        let (boards, invalid): (Vec<String>, Vec<String>) = std::mem::take(&mut self.local_boards)
+            .into_iter()
+            .partition(|p| crate::local::is_board_file(Path::new(p)));
+        if !invalid.is_empty() {
+            problems.push(format!(
+                "local_boards must be .json or .md files, ignoring {invalid:?}"
+            ));
+        }
+        self.local_boards = boards;
+
+        if let Some(gitlab) = &self.gitlab {
+            if !gitlab.url.starts_with("https://") && !gitlab.url.starts_with("http://") {
+                problems.push(format!(
+                    "gitlab.url must start with https:// or http://, got {:?}",
+                    gitlab.url
+                ));
+                self.gitlab = None;
+            }
+        }
+
+        if let Some(gitea) = &self.gitea {
+            if !gitea.url.starts_with("https://") && !gitea.url.starts_with("http://") {
+                problems.push(format!(
+                    "gitea.url must start with https:// or http://, got {:?}",
+                    gitea.url
+                ));
+                self.gitea = None;
+            } else if gitea.column_scope.trim().is_empty() || gitea.column_scope.contains('/') {
+                problems.push(format!(
+                    "gitea.column_scope must be a label scope without slashes, got {:?}",
+                    gitea.column_scope
+                ));
+                if let Some(gitea) = &mut self.gitea {
+                    gitea.column_scope = GiteaConfig::default_column_scope();
+                }
+            }
+        }
+
+        if !COLUMN_WIDTH_RANGE.contains(&self.layout.column_width) {
+            problems.push(format!(
+                "layout.column_width must be between {} and {}, got {}",
+                COLUMN_WIDTH_RANGE.start(),
+                COLUMN_WIDTH_RANGE.end(),
+                self.layout.column_width
+            ));
+            self.layout.column_width = defaults.layout.column_width;
+        }
+
+        if self.layout.column_gap > 32 {
+            problems.push(format!(
+                "layout.column_gap must be at most 32, got {}",
+                self.layout.column_gap
+            ));
+            self.layout.column_gap = defaults.layout.column_gap;
+        }
+
+        if self.layout.padding > 64 {
+            problems.push(format!(
+                "layout.padding must be at most 64, got {}",
+                self.layout.padding
+            ));
+            self.layout.padding = defaults.layout.padding;
+        }
+
+        let unknown = self
+            .colors
+            .columns
+            .iter()
+            .any(|c| !COLUMN_COLORS.contains(&c.as_str()));
+        if unknown || self.colors.columns.is_empty() {
+            problems.push(format!(
+                "colors.columns must be a non-empty list of {}, got {:?}",
+                COLUMN_COLORS.join(", "),
+                self.colors.columns
+            ));
+            self.colors = defaults.colors;
+        }
+
+        problems
+    }
+}
+
+/// Parse and validate the contents of a config file
+fn parse_config(toml: &str) -> Result<Config, String> {
+    let mut config: Config = toml::from_str(toml).map_err(|e| e.to_string())?;
+    for problem in config.validate() {
+        warn!("Invalid config value, using the default: {problem}");
+    }
+    Ok(config)
+}
+
+/// Directory given with `--config-dir`, replacing the platform default
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep all of minik's files in another directory; only the first call counts
+pub fn set_config_dir(dir: PathBuf) {
+    if CONFIG_DIR.set(dir).is_err() {
+        warn!("Config directory already set, ignoring the new one");
+    }
+}
+
+/// Point the config directory at a scratch directory shared by the tests of this process
+#[cfg(test)]
+pub fn use_test_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("minik-test-{}", std::process::id()));
+    let _ = CONFIG_DIR.set(dir.clone());
+    std::fs::create_dir_all(&dir).expect("create test config directory");
+    dir
+}
+
+/// Get the directory holding the config, state and caches
+pub fn config_dir() -> Option<PathBuf> {
+    match CONFIG_DIR.get() {
+        Some(dir) => Some(dir.clone()),
+        None => dirs::config_dir().map(|p| p.join("minik")),
+    }
+}
+
+/// Get the path of the config file
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|p| p.join("config.toml"))
+}
+
+/// Read the config file, `None` if it is missing or cannot be parsed
+fn read_config(path: &Path) -> Option<Config> {
+    match std::fs::read_to_string(path) {
+        Ok(toml) => match parse_config(&toml) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                error!("Failed to parse {path:?}: {e}");
+                None
+            }
+        },
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read {path:?}: {e}");
+            }
+            None
+        }
+    }
+}
+
+/// Thread-safe holder of the current config
+pub struct ConfigStore(Mutex<Config>);
+
+impl ConfigStore {
+    /// Load the config file, using defaults when there is none
+    pub fn load() -> Self {
+        let config = config_path()
+            .and_then(|path| {
+                let config = read_config(&path)?;
+                info!("Loaded config from {path:?}");
+                Some(config)
+            })
+            .unwrap_or_default();
+        Self(Mutex::new(config))
+    }
+
+    /// Get the current config
+    pub fn get(&self) -> Config {
+        match self.0.lock() {
+            Ok(config) => config.clone(),
+            Err(e) => {
+                error!("Failed to lock config: {e}");
+                Config::default()
+            }
+        }
+    }
+
+    /// Read the config file again, returning the new config if it changed
+    ///
+    /// A file that no longer parses keeps the current config, so a
+    /// half-saved edit does not reset everything to defaults.
+    fn reload(&self, path: &Path) -> Option<Config> {
+        let config = if path.exists() {
+            read_config(path)?
+        } else {
+            Config::default()
+        };
+
+        let mut current = self.0.lock().ok()?;
+        if *current == config {
+            return None;
+        }
+        *current = config.clone();
+        Some(config)
+    }
+}
+
+/// Watch the config file and call `on_change` with every new config
+///
+/// The watcher stops when the returned value is dropped.
+pub fn watch_config<R, F>(app_handle: AppHandle<R>, on_change: F) -> Option<RecommendedWatcher>
+where
+    R: tauri::Runtime,
+    F: Fn(&AppHandle<R>, Config) + Send + 'static,
+{
+    let path = config_path()?;
+    let dir = path.parent()?.to_path_buf();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        error!("Failed to create config directory: {e}");
+        return None;
+    }
+
+    // Editors often replace the file instead of writing to it, so watch the directory
+    let watched = path.clone();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.paths.contains(&watched) => {
+                debug!("Config file changed: {:?}", event.kind);
+                if let Some(config) = app_handle.state::<ConfigStore>().reload(&watched) {
+                    info!("Reloaded config from {watched:?}");
+                    on_change(&app_handle, config);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Config watcher error: {e}"),
+        })
+        .map_err(|e| error!("Failed to create config watcher: {e}"))
+        .ok()?;
+
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| error!("Failed to watch {dir:?}: {e}"))
+        .ok()?;
+    info!("Watching {path:?} for changes");
+    Some(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> (Config, Vec<String>) {
+        let mut config: Config = toml::from_str(toml).unwrap();
+        let problems = config.validate();
+        (config, problems)
+    }
+
+    #[test]
+    fn an_empty_file_is_the_default_config() {
+        assert_eq!(parse(""), (Config::default(), Vec::new()));
+    }
+
+    #[test]
+    fn out_of_range_values_fall_back_to_their_defaults() {
+        let (config, problems) = parse(
+            r#"
+            refresh_interval_secs = 5
+            log_level = "loud"
+            local_boards = ["board.md", "notes.txt"]
+
+            [layout]
+            column_width = 2000
+            column_gap = 8
+
+            [colors]
+            columns = ["blue", "teal"]
+            "#,
+        );
+
+        let defaults = Config::default();
+        assert_eq!(config.refresh_interval_secs, defaults.refresh_interval_secs);
+        assert_eq!(config.log_level, defaults.log_level);
+        assert_eq!(config.local_boards, ["board.md"]);
+        assert_eq!(config.layout.column_width, defaults.layout.column_width);
+        assert_eq!(config.layout.column_gap, 8);
+        assert_eq!(config.colors, defaults.colors);
+        assert_eq!(problems.len(), 5, "{problems:#?}");
+        assert!(
+            problems[0].starts_with("refresh_interval_secs"),
+            "{problems:#?}"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        for toml in [
+            "refresh_interval = 60",
+            "[layout]\ncolumn_widht = 200",
+            "[gitlab]\nurl = \"https://gitlab.com\"\ngroup = \"mine\"",
+        ] {
+            let error = parse_config(toml).unwrap_err();
+            assert!(error.contains("unknown field"), "{toml}: {error}");
+        }
+    }
+
+    #[test]
+    fn a_backend_without_an_http_url_is_disabled() {
+        let (config, problems) = parse(
+            r#"
+            [gitlab]
+            url = "gitlab.example.com"
+
+            [gitea]
+            url = "ftp://gitea.example.com"
+            "#,
+        );
+
+        assert_eq!(config.gitlab, None);
+        assert_eq!(config.gitea, None);
+        assert_eq!(problems.len(), 2, "{problems:#?}");
+
+        let (config, problems) = parse("[gitea]\nurl = \"http://localhost:3000\"");
+        assert!(problems.is_empty(), "{problems:#?}");
+        assert_eq!(config.gitea.unwrap().column_scope, "status");
+    }
+}
@@ -0,0 +1,256 @@
+//! Local control channel for scripts and editor plugins
+//!
+//! The running app listens on a Unix domain socket, `control.sock` in the
+//! config directory, or on the named pipe `minik-control-<user SID>` on
+//! Windows. Either is only open to the user running minik. Every line sent is
+//! a JSON-RPC 2.0 request and every request with an `id` gets one line back.
+
+use futures::future::BoxFuture;
+use interprocess::local_socket::tokio::prelude::*;
+use interprocess::local_socket::ListenerOptions;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// A JSON-RPC request
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC response carrying either a result or an error
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorObject>,
+}
+
+#[derive(Serialize)]
+struct ErrorObject {
+    code: i64,
+    message: String,
+}
+
+/// Why a control request failed
+#[derive(Debug)]
+pub enum ControlError {
+    /// No such method
+    UnknownMethod(String),
+    /// The parameters do not fit the method
+    InvalidParams(String),
+    /// The method ran and failed
+    Failed(String),
+}
+
+impl ControlError {
+    fn into_object(self) -> ErrorObject {
+        let (code, message) = match self {
+            Self::UnknownMethod(method) => (-32601, format!("Unknown method {method:?}")),
+            Self::InvalidParams(message) => (-32602, message),
+            Self::Failed(message) => (-32000, message),
+        };
+        ErrorObject { code, message }
+    }
+}
+
+impl From<String> for ControlError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+/// Parse the parameters of a request
+pub fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, ControlError> {
+    // Methods without parameters may be called with none at all
+    let params = if params.is_null() {
+        Value::Object(serde_json::Map::new())
+    } else {
+        params
+    };
+    serde_json::from_value(params).map_err(|e| ControlError::InvalidParams(e.to_string()))
+}
+
+/// Runs a method with its parameters
+pub type Handler =
+    Arc<dyn Fn(String, Value) -> BoxFuture<'static, Result<Value, ControlError>> + Send + Sync>;
+
+/// Get the name of the control socket
+fn socket_name() -> std::io::Result<interprocess::local_socket::Name<'static>> {
+    #[cfg(windows)]
+    {
+        use interprocess::local_socket::GenericNamespaced;
+        // Every user on the machine gets their own pipe
+        format!("minik-control-{}", pipe::current_user_sid()?).to_ns_name::<GenericNamespaced>()
+    }
+    #[cfg(not(windows))]
+    {
+        use interprocess::local_socket::GenericFilePath;
+        let path = crate::config::config_dir()
+            .ok_or_else(|| std::io::Error::other("Could not determine config directory"))?
+            .join("control.sock");
+        std::fs::create_dir_all(path.parent().unwrap_or(&path))?;
+        // Only one minik runs at a time, so a leftover socket is from a crashed session
+        let _ = std::fs::remove_file(&path);
+        path.to_fs_name::<GenericFilePath>()
+            .map(|name| name.into_owned())
+    }
+}
+
+/// Access control for the named pipe
+#[cfg(windows)]
+mod pipe {
+    use interprocess::os::windows::security_descriptor::SecurityDescriptor;
+    use std::io;
+    use widestring::{U16CStr, U16CString};
+    use windows_sys::Win32::Foundation::{CloseHandle, LocalFree, HANDLE};
+    use windows_sys::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    /// Get the SID of the user running minik, like `S-1-5-21-...`
+    pub fn current_user_sid() -> io::Result<String> {
+        // SAFETY: every pointer handed to Windows points into a live, large enough
+        // buffer, and the token and SID string are released on every path
+        unsafe {
+            let mut token: HANDLE = std::ptr::null_mut();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // The first call only reports the size needed
+            let mut len = 0;
+            GetTokenInformation(token, TokenUser, std::ptr::null_mut(), 0, &mut len);
+            // u64 keeps the buffer aligned for TOKEN_USER
+            let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+            let ok =
+                GetTokenInformation(token, TokenUser, buffer.as_mut_ptr().cast(), len, &mut len);
+            let error = io::Error::last_os_error();
+            CloseHandle(token);
+            if ok == 0 {
+                return Err(error);
+            }
+
+            let user = &*buffer.as_ptr().cast::<TOKEN_USER>();
+            let mut sid = std::ptr::null_mut();
+            if ConvertSidToStringSidW(user.User.Sid, &mut sid) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let result = U16CStr::from_ptr_str(sid).to_string_lossy();
+            LocalFree(sid.cast());
+            Ok(result)
+        }
+    }
+
+    /// A security descriptor granting access to the current user and nobody else
+    pub fn current_user_only() -> io::Result<SecurityDescriptor> {
+        // Protected DACL, so nothing is inherited, with full access for the user
+        let sddl = format!("D:P(A;;GA;;;{})", current_user_sid()?);
+        let sddl = U16CString::from_str(sddl).map_err(io::Error::other)?;
+        SecurityDescriptor::deserialize(&sddl)
+    }
+}
+
+/// Listen for control requests in the background
+pub fn serve(handler: Handler) {
+    tauri::async_runtime::spawn(async move {
+        let listener = match socket_name().and_then(|name| {
+            let options = ListenerOptions::new().name(name);
+            #[cfg(unix)]
+            let options = {
+                use interprocess::os::unix::local_socket::ListenerOptionsExt;
+                // Only the user running minik may talk to it
+                options.mode(0o600)
+            };
+            // Only the user running minik may talk to it; interprocess always
+            // creates the pipe with PIPE_REJECT_REMOTE_CLIENTS, keeping SMB clients out
+            #[cfg(windows)]
+            let options = {
+                use interprocess::os::windows::local_socket::ListenerOptionsExt;
+                options.security_descriptor(pipe::current_user_only()?)
+            };
+            options.create_tokio()
+        }) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to start control channel: {e}");
+                return;
+            }
+        };
+        info!("Control channel listening");
+
+        loop {
+            match listener.accept().await {
+                Ok(stream) => {
+                    let handler = handler.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = handle_connection(stream, handler).await {
+                            debug!("Control connection closed: {e}");
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept control connection: {e}"),
+            }
+        }
+    });
+}
+
+/// Answer the requests of one client until it disconnects
+async fn handle_connection(stream: LocalSocketStream, handler: Handler) -> std::io::Result<()> {
+    let (receiver, mut sender) = stream.split();
+    let mut lines = BufReader::new(receiver).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                debug!("Control request: {}", request.method);
+                let result = handler(request.method, request.params).await;
+                // Requests without an id are notifications and get no answer
+                let Some(id) = request.id else {
+                    continue;
+                };
+                match result {
+                    Ok(result) => Response {
+                        jsonrpc: "2.0",
+                        id,
+                        result: Some(result),
+                        error: None,
+                    },
+                    Err(e) => Response {
+                        jsonrpc: "2.0",
+                        id,
+                        result: None,
+                        error: Some(e.into_object()),
+                    },
+                }
+            }
+            Err(e) => Response {
+                jsonrpc: "2.0",
+                id: Value::Null,
+                result: None,
+                error: Some(ErrorObject {
+                    code: -32700,
+                    message: format!("Invalid request: {e}"),
+                }),
+            },
+        };
+
+        let mut json = serde_json::to_vec(&response)?;
+        json.push(b'\n');
+        sender.write_all(&json).await?;
+    }
+    Ok(())
+}
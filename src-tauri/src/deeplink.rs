@@ -0,0 +1,125 @@
+//! `minik://` links that open a board from team docs or the browser
+//!
+//! `minik://project/<org>/<number>?view=<n>&filter=<query>` selects the
+//! project in the running instance. Only the `assignee:@me` filter maps to
+//! something minik can show; other filter terms are ignored.
+
+use log::warn;
+use tauri::Url;
+
+/// URL scheme registered with the operating system
+pub const SCHEME: &str = "minik";
+
+/// A parsed `minik://project/...` link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLink {
+    /// Project as `org/number`
+    pub project: String,
+    /// Number of the GitHub project view the link was copied from
+    pub view: Option<u32>,
+    /// GitHub project filter query
+    pub filter: Option<String>,
+}
+
+impl DeepLink {
+    /// Parse a `minik://` URL
+    pub fn parse(url: &Url) -> Result<Self, String> {
+        if url.scheme() != SCHEME {
+            return Err(format!("Not a {SCHEME}:// link: {url}"));
+        }
+        if url.host_str() != Some("project") {
+            return Err(format!("Unsupported {SCHEME}:// link: {url}"));
+        }
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let [org, number] = segments[..] else {
+            return Err(format!(
+                "Expected {SCHEME}://project/<org>/<number>, got {url}"
+            ));
+        };
+        let number: u64 = number
+            .parse()
+            .map_err(|_| format!("Invalid project number {number:?} in {url}"))?;
+
+        let mut link = Self {
+            project: format!("{org}/{number}"),
+            view: None,
+            filter: None,
+        };
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "view" => link.view = value.parse().ok(),
+                "filter" => link.filter = Some(value.into_owned()),
+                _ => warn!("Ignoring unknown parameter {key:?} in {url}"),
+            }
+        }
+        Ok(link)
+    }
+
+    /// Whether the filter limits the board to the user's own items, `None` without a filter
+    pub fn only_my_items(&self) -> Option<bool> {
+        let filter = self.filter.as_deref()?;
+        let mut mine = false;
+        for term in filter.split_whitespace() {
+            if term == "assignee:@me" {
+                mine = true;
+            } else {
+                warn!("Ignoring unsupported filter term {term:?}");
+            }
+        }
+        Some(mine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(link: &str) -> Result<DeepLink, String> {
+        DeepLink::parse(&Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn parses_a_link_copied_from_a_project_view() {
+        let link = parse("minik://project/codeGROOVE-dev/3?view=2&filter=assignee:@me").unwrap();
+
+        assert_eq!(
+            link,
+            DeepLink {
+                project: "codeGROOVE-dev/3".to_string(),
+                view: Some(2),
+                filter: Some("assignee:@me".to_string()),
+            }
+        );
+        assert_eq!(link.only_my_items(), Some(true));
+    }
+
+    #[test]
+    fn rejects_links_that_do_not_name_a_project() {
+        for link in [
+            "minik://project/codeGROOVE-dev/three",
+            "minik://project/codeGROOVE-dev/-3",
+            "minik://project/codeGROOVE-dev",
+            "minik://project/codeGROOVE-dev/3/items",
+            "minik://board/codeGROOVE-dev/3",
+            "https://project/codeGROOVE-dev/3",
+        ] {
+            assert!(parse(link).is_err(), "{link}");
+        }
+    }
+
+    #[test]
+    fn only_assignee_me_shows_my_items() {
+        let link = parse("minik://project/org/1").unwrap();
+        assert_eq!(link.only_my_items(), None);
+
+        let link = parse("minik://project/org/1?filter=label:bug%20status:Todo").unwrap();
+        assert_eq!(link.only_my_items(), Some(false));
+
+        let link = parse("minik://project/org/1?filter=label:bug%20assignee:@me").unwrap();
+        assert_eq!(link.only_my_items(), Some(true));
+    }
+}
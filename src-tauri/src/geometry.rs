@@ -0,0 +1,173 @@
+//! Window geometry that survives monitor changes
+//!
+//! Positions are remembered per display configuration, identified by the
+//! layout of all connected monitors, and checked against the monitors that are
+//! actually connected before a window is moved there.
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// How much of a window must be on some monitor for it to count as visible
+const MIN_VISIBLE: i32 = 50;
+
+/// Position and size of a window in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Area covered by a monitor in physical pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Screen {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&tauri::Monitor> for Screen {
+    fn from(monitor: &tauri::Monitor) -> Self {
+        Self {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+}
+
+impl Screen {
+    fn right(&self) -> i32 {
+        self.x.saturating_add(self.width as i32)
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y.saturating_add(self.height as i32)
+    }
+
+    /// Get the size of the part of a window on this screen
+    fn overlap(&self, geometry: &WindowGeometry) -> (i32, i32) {
+        let right = geometry.x.saturating_add(geometry.width as i32);
+        let bottom = geometry.y.saturating_add(geometry.height as i32);
+        (
+            right.min(self.right()) - geometry.x.max(self.x),
+            bottom.min(self.bottom()) - geometry.y.max(self.y),
+        )
+    }
+
+    /// Get the squared distance from a point to this screen, zero when inside it
+    fn distance_squared(&self, x: i32, y: i32) -> i64 {
+        let dx = i64::from((self.x - x).max(0).max(x - self.right()));
+        let dy = i64::from((self.y - y).max(0).max(y - self.bottom()));
+        dx * dx + dy * dy
+    }
+}
+
+/// Identify a display configuration by the layout of its monitors
+pub fn display_key(screens: &[Screen]) -> String {
+    let mut layout: Vec<String> = screens
+        .iter()
+        .map(|s| format!("{}x{}@{},{}", s.width, s.height, s.x, s.y))
+        .collect();
+    layout.sort();
+    layout.join("|")
+}
+
+/// Move a window onto the nearest screen unless enough of it is visible already
+pub fn fit_on_screen(geometry: WindowGeometry, screens: &[Screen]) -> WindowGeometry {
+    let visible = screens.iter().any(|screen| {
+        let (width, height) = screen.overlap(&geometry);
+        width >= MIN_VISIBLE.min(geometry.width as i32)
+            && height >= MIN_VISIBLE.min(geometry.height as i32)
+    });
+    if visible {
+        return geometry;
+    }
+
+    let center_x = geometry.x.saturating_add(geometry.width as i32 / 2);
+    let center_y = geometry.y.saturating_add(geometry.height as i32 / 2);
+    let Some(screen) = screens
+        .iter()
+        .min_by_key(|screen| screen.distance_squared(center_x, center_y))
+    else {
+        return geometry;
+    };
+
+    let max_x = screen.right() - (geometry.width as i32).min(screen.width as i32);
+    let max_y = screen.bottom() - (geometry.height as i32).min(screen.height as i32);
+    let fitted = WindowGeometry {
+        x: geometry.x.clamp(screen.x, max_x.max(screen.x)),
+        y: geometry.y.clamp(screen.y, max_y.max(screen.y)),
+        ..geometry
+    };
+
+    info!(
+        "Window at ({}, {}) is off-screen, moving it to ({}, {})",
+        geometry.x, geometry.y, fitted.x, fitted.y
+    );
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAPTOP: Screen = Screen {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    const EXTERNAL: Screen = Screen {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    fn window(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn a_window_on_a_removed_monitor_moves_to_the_nearest_screen() {
+        let left_of_laptop = window(-2000, 100, 800, 600);
+        assert_eq!(
+            fit_on_screen(left_of_laptop, &[LAPTOP, EXTERNAL]),
+            window(0, 100, 800, 600)
+        );
+
+        let right_of_external = window(5000, 200, 800, 600);
+        assert_eq!(
+            fit_on_screen(right_of_external, &[LAPTOP, EXTERNAL]),
+            window(3680, 200, 800, 600)
+        );
+    }
+
+    #[test]
+    fn a_visible_window_is_left_alone() {
+        for geometry in [
+            window(100, 100, 800, 600),
+            window(-700, 100, 800, 600),
+            window(1500, 900, 800, 600),
+        ] {
+            assert_eq!(fit_on_screen(geometry, &[LAPTOP, EXTERNAL]), geometry);
+        }
+        let anywhere = window(-5000, -5000, 800, 600);
+        assert_eq!(fit_on_screen(anywhere, &[]), anywhere);
+    }
+
+    #[test]
+    fn a_window_larger_than_the_screen_goes_to_its_corner() {
+        let huge = window(5000, 5000, 3000, 2000);
+        assert_eq!(fit_on_screen(huge, &[LAPTOP]), window(0, 0, 3000, 2000));
+    }
+}
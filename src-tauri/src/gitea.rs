@@ -0,0 +1,498 @@
+//! Gitea and Forgejo label boards
+//!
+//! These stand in for project boards, which neither server exposes through the
+//! REST API: cards on a Gitea project board are not shown. A board is built
+//! from a repository's exclusive scoped labels instead: every label named
+//! `<scope>/<column>` is a column, framed by Open for issues without one and
+//! Closed. Moving a card swaps the issue's scoped label, and closes or reopens
+//! it for the Closed column.
+
+use crate::backend::BoardBackend;
+use crate::config::GiteaConfig;
+use crate::github::{
+    ItemFieldState, Organization, Project, ProjectColumn, ProjectData, ProjectItem,
+};
+use crate::rest::{self, RestClient};
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// Prefix of the organization and project IDs of Gitea boards
+pub const PREFIX: &str = "gitea";
+
+/// Environment variable holding the access token when config.toml has none
+const TOKEN_ENV: &str = "GITEA_TOKEN";
+
+/// Column of open issues without a column label
+const OPEN_COLUMN: &str = "open";
+
+/// Column of closed issues
+const CLOSED_COLUMN: &str = "closed";
+
+/// Closed issues shown on a board
+const CLOSED_LIMIT: u32 = 20;
+
+/// Page size asked for; servers may cap it lower
+const PAGE_LIMIT: u32 = 50;
+
+/// Split a project ID, `gitea:<owner>/<repo>`, into owner and repository
+fn parse_project_id(project_id: &str) -> Result<(&str, &str)> {
+    project_id
+        .strip_prefix(PREFIX)
+        .and_then(|r| r.strip_prefix(':'))
+        .and_then(|r| r.split_once('/'))
+        .filter(|(owner, repo)| !owner.is_empty() && !repo.is_empty())
+        .with_context(|| format!("{project_id:?} is not a Gitea repository"))
+}
+
+/// Split an item ID, `<owner>/<repo>#<number>`, into repository and issue number
+fn parse_item_id(item_id: &str) -> Result<(&str, &str, u64)> {
+    item_id
+        .rsplit_once('#')
+        .and_then(|(repo, number)| {
+            let (owner, repo) = repo.split_once('/')?;
+            Some((owner, repo, number.parse().ok()?))
+        })
+        .with_context(|| format!("{item_id:?} is not a Gitea issue"))
+}
+
+/// A label that makes a column
+struct ColumnLabel {
+    id: u64,
+    name: String,
+}
+
+impl ColumnLabel {
+    fn column_id(&self) -> String {
+        format!("label:{}", self.id)
+    }
+}
+
+/// Get the column an issue sits in
+fn issue_column(issue: &Value, columns: &[ColumnLabel]) -> String {
+    if issue["state"].as_str() == Some("closed") {
+        return CLOSED_COLUMN.to_string();
+    }
+    let labels = label_ids(issue);
+    columns
+        .iter()
+        .find(|column| labels.contains(&column.id))
+        .map_or_else(|| OPEN_COLUMN.to_string(), ColumnLabel::column_id)
+}
+
+fn label_ids(issue: &Value) -> Vec<u64> {
+    issue["labels"]
+        .as_array()
+        .map(|labels| labels.iter().filter_map(|l| l["id"].as_u64()).collect())
+        .unwrap_or_default()
+}
+
+fn issue_item(owner: &str, repo: &str, issue: &Value, columns: &[ColumnLabel]) -> ProjectItem {
+    ProjectItem {
+        id: format!("{owner}/{repo}#{}", issue["number"]),
+        title: issue["title"].as_str().unwrap_or_default().to_string(),
+        assignees: issue["assignees"]
+            .as_array()
+            .map(|users| {
+                users
+                    .iter()
+                    .filter_map(|u| u["login"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        column_id: issue_column(issue, columns),
+        labels: issue["labels"]
+            .as_array()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|l| l["name"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        url: issue["html_url"].as_str().map(String::from),
+        updated_at: issue["updated_at"].as_str().map(String::from),
+        content_id: None,
+    }
+}
+
+/// Client for the Gitea REST API, which Forgejo serves as well
+pub struct GiteaClient {
+    rest: RestClient,
+    owners: Vec<String>,
+    column_scope: String,
+}
+
+impl GiteaClient {
+    /// Create a client from the `[gitea]` section of the config
+    pub fn new(config: &GiteaConfig) -> Result<Self> {
+        let token = rest::token("Gitea", config.token.as_deref(), TOKEN_ENV)?;
+        Ok(Self {
+            rest: RestClient::new(
+                "Gitea",
+                &config.url,
+                &["api", "v1"],
+                ("Authorization", format!("token {token}")),
+            )?,
+            owners: config.owners.clone(),
+            column_scope: config.column_scope.clone(),
+        })
+    }
+
+    async fn get<S: AsRef<str>>(&self, segments: &[S], query: &[(&str, String)]) -> Result<Value> {
+        self.rest.get(segments, query).await
+    }
+
+    /// Get every page of a list
+    ///
+    /// Repository search wraps its results in `data`, other lists are plain arrays.
+    async fn get_all<S: AsRef<str>>(
+        &self,
+        segments: &[S],
+        query: &[(&str, String)],
+    ) -> Result<Vec<Value>> {
+        let mut results = Vec::new();
+        for page in 1.. {
+            let mut paged = query.to_vec();
+            paged.push(("limit", PAGE_LIMIT.to_string()));
+            paged.push(("page", page.to_string()));
+            let (data, headers) = self
+                .rest
+                .request(Method::GET, self.rest.url(segments), &paged, None)
+                .await?;
+            let total: Option<usize> =
+                rest::header(&headers, "x-total-count").and_then(|v| v.parse().ok());
+            let values = data
+                .as_array()
+                .or_else(|| data["data"].as_array())
+                .cloned()
+                .unwrap_or_default();
+            if values.is_empty() {
+                break;
+            }
+            results.extend(values);
+            if total.is_some_and(|total| results.len() >= total) {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Get a repository's column labels, in the order they were created
+    async fn column_labels(&self, owner: &str, repo: &str) -> Result<Vec<ColumnLabel>> {
+        let mut labels = self.get_all(&["repos", owner, repo, "labels"], &[]).await?;
+        // Organization labels can be used on the repository too; users have none
+        match self.get_all(&["orgs", owner, "labels"], &[]).await {
+            Ok(org_labels) => labels.extend(org_labels),
+            Err(e) if rest::is_not_found(&e) => debug!("No organization labels for {owner}: {e}"),
+            Err(e) => return Err(e),
+        }
+
+        let prefix = format!("{}/", self.column_scope);
+        let mut columns: Vec<ColumnLabel> = labels
+            .iter()
+            .filter_map(|label| {
+                let name = label["name"].as_str()?.strip_prefix(&prefix)?;
+                Some(ColumnLabel {
+                    id: label["id"].as_u64()?,
+                    name: name.to_string(),
+                })
+            })
+            .collect();
+        columns.sort_by_key(|c| c.id);
+        Ok(columns)
+    }
+
+    /// Get an account by name, which may be a user or an organization
+    async fn owner(&self, owner: &str) -> Result<Organization> {
+        let user = self.get(&["users", owner], &[]).await?;
+        Ok(Organization {
+            id: user["id"].as_u64().unwrap_or_default(),
+            login: format!("{PREFIX}:{}", user["login"].as_str().unwrap_or(owner)),
+            name: user["full_name"]
+                .as_str()
+                .filter(|n| !n.is_empty())
+                .map(String::from),
+        })
+    }
+}
+
+#[async_trait]
+impl BoardBackend for GiteaClient {
+    fn name(&self) -> &str {
+        "Gitea"
+    }
+
+    async fn is_reachable(&self) -> bool {
+        self.rest.is_reachable().await
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>> {
+        let owners = if self.owners.is_empty() {
+            let user = self.get(&["user"], &[]).await?;
+            let mut owners = vec![user["login"].as_str().unwrap_or_default().to_string()];
+            owners.extend(
+                self.get_all(&["user", "orgs"], &[])
+                    .await?
+                    .iter()
+                    .filter_map(|org| {
+                        org["username"]
+                            .as_str()
+                            .or_else(|| org["name"].as_str())
+                            .map(String::from)
+                    }),
+            );
+            owners
+        } else {
+            self.owners.clone()
+        };
+
+        let mut organizations = Vec::new();
+        for owner in &owners {
+            organizations.push(self.owner(owner).await?);
+        }
+        info!("Found {} Gitea owners", organizations.len());
+        Ok(organizations)
+    }
+
+    async fn list_projects(&self, org: &str) -> Result<Vec<Project>> {
+        let owner = org
+            .strip_prefix(PREFIX)
+            .and_then(|o| o.strip_prefix(':'))
+            .with_context(|| format!("{org:?} is not a Gitea owner"))?;
+        let account = self.owner(owner).await?;
+
+        let repos = self
+            .get_all(
+                &["repos", "search"],
+                &[
+                    ("uid", account.id.to_string()),
+                    ("exclusive", "true".to_string()),
+                    ("archived", "false".to_string()),
+                ],
+            )
+            .await?;
+
+        Ok(repos
+            .iter()
+            .filter(|repo| repo["has_issues"].as_bool().unwrap_or(true))
+            .map(|repo| {
+                let full_name = repo["full_name"].as_str().unwrap_or_default();
+                Project {
+                    id: format!("{PREFIX}:{full_name}"),
+                    title: repo["name"].as_str().unwrap_or(full_name).to_string(),
+                    url: repo["html_url"].as_str().unwrap_or_default().to_string(),
+                    number: repo["id"].as_u64().unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn project_data(&self, project_id: &str) -> Result<ProjectData> {
+        let (owner, repo) = parse_project_id(project_id)?;
+
+        let repo_path = ["repos", owner, repo];
+        let issues_path = ["repos", owner, repo, "issues"];
+        let open_query = [
+            ("state", "open".to_string()),
+            ("type", "issues".to_string()),
+        ];
+        let closed_query = [
+            ("state", "closed".to_string()),
+            ("type", "issues".to_string()),
+            ("limit", CLOSED_LIMIT.to_string()),
+        ];
+        let (data, labels, open, closed) = futures::try_join!(
+            self.get(&repo_path, &[]),
+            self.column_labels(owner, repo),
+            self.get_all(&issues_path, &open_query),
+            self.get(&issues_path, &closed_query),
+        )?;
+
+        let items: Vec<ProjectItem> = open
+            .iter()
+            .chain(closed.as_array().into_iter().flatten())
+            .map(|issue| issue_item(owner, repo, issue, &labels))
+            .collect();
+
+        let mut columns = vec![ProjectColumn {
+            id: OPEN_COLUMN.to_string(),
+            items_count: 0,
+            name: "Open".to_string(),
+        }];
+        columns.extend(labels.iter().map(|label| ProjectColumn {
+            id: label.column_id(),
+            items_count: 0,
+            name: label.name.clone(),
+        }));
+        columns.push(ProjectColumn {
+            id: CLOSED_COLUMN.to_string(),
+            items_count: 0,
+            name: "Closed".to_string(),
+        });
+        for column in &mut columns {
+            column.items_count = items.iter().filter(|i| i.column_id == column.id).count();
+        }
+        info!(
+            "Fetched Gitea board {owner}/{repo} with {} items",
+            items.len()
+        );
+
+        Ok(ProjectData {
+            project: Project {
+                id: project_id.to_string(),
+                title: data["name"].as_str().unwrap_or(repo).to_string(),
+                url: data["html_url"].as_str().unwrap_or_default().to_string(),
+                number: data["id"].as_u64().unwrap_or_default(),
+            },
+            columns,
+            hidden_columns: Vec::new(),
+            items,
+            // Column labels stand in for the status field
+            status_field_id: self.column_scope.clone(),
+            fetched_at: Some(chrono::Utc::now()),
+            stale: false,
+        })
+    }
+
+    async fn item_field_state(&self, item_id: &str, _field_id: &str) -> Result<ItemFieldState> {
+        let (owner, repo, number) = parse_item_id(item_id)?;
+        let number = number.to_string();
+        let issue_path = ["repos", owner, repo, "issues", number.as_str()];
+        let (labels, issue) =
+            futures::try_join!(self.column_labels(owner, repo), self.get(&issue_path, &[]),)?;
+
+        Ok(ItemFieldState {
+            option_id: Some(issue_column(&issue, &labels)),
+            updated_at: issue["updated_at"].as_str().map(String::from),
+        })
+    }
+
+    async fn move_item(
+        &self,
+        _project_id: &str,
+        item_id: &str,
+        _field_id: &str,
+        column_id: &str,
+    ) -> Result<Option<String>> {
+        let (owner, repo, number) = parse_item_id(item_id)?;
+        let number = number.to_string();
+        let issue_path = ["repos", owner, repo, "issues", number.as_str()];
+        let (columns, issue) =
+            futures::try_join!(self.column_labels(owner, repo), self.get(&issue_path, &[]),)?;
+
+        let target = match column_id {
+            OPEN_COLUMN | CLOSED_COLUMN => None,
+            _ => Some(
+                columns
+                    .iter()
+                    .find(|c| c.column_id() == column_id)
+                    .with_context(|| format!("No column {column_id:?} on this board"))?,
+            ),
+        };
+
+        // Swap the column label, keeping every other label of the issue
+        let mut labels: Vec<u64> = label_ids(&issue)
+            .into_iter()
+            .filter(|id| !columns.iter().any(|c| c.id == *id))
+            .collect();
+        labels.extend(target.map(|c| c.id));
+
+        info!("Moving Gitea issue {item_id} to {column_id}");
+        let labels_url =
+            self.rest
+                .url(&["repos", owner, repo, "issues", number.as_str(), "labels"]);
+        self.rest
+            .request(
+                Method::PUT,
+                labels_url,
+                &[],
+                Some(&json!({ "labels": labels })),
+            )
+            .await?;
+
+        let state = if column_id == CLOSED_COLUMN {
+            "closed"
+        } else {
+            "open"
+        };
+        let (issue, _) = self
+            .rest
+            .request(
+                Method::PATCH,
+                self.rest.url(&issue_path),
+                &[],
+                Some(&json!({ "state": state })),
+            )
+            .await?;
+        Ok(issue["updated_at"].as_str().map(String::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(server: &MockServer) -> GiteaClient {
+        GiteaClient::new(&GiteaConfig {
+            url: server.uri(),
+            token: Some("test-token".to_string()),
+            owners: Vec::new(),
+            column_scope: "status".to_string(),
+        })
+        .unwrap()
+    }
+
+    async fn mount_repo_labels(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/me/tasks/labels"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-total-count", "3")
+                    .set_body_json(json!([
+                        { "id": 2, "name": "status/Doing" },
+                        { "id": 1, "name": "status/Todo" },
+                        { "id": 3, "name": "bug" }
+                    ])),
+            )
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn users_without_organization_labels_use_repository_labels() {
+        let server = MockServer::start().await;
+        mount_repo_labels(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/orgs/me/labels"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let columns = client(&server).column_labels("me", "tasks").await.unwrap();
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Todo", "Doing"]);
+    }
+
+    #[tokio::test]
+    async fn organization_label_errors_other_than_not_found_are_reported() {
+        let server = MockServer::start().await;
+        mount_repo_labels(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/orgs/me/labels"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .column_labels("me", "tasks")
+            .await
+            .err()
+            .expect("a 401 must not be taken for a user without labels");
+        assert!(err.to_string().contains("401"), "{err}");
+    }
+}
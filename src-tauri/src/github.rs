@@ -3,13 +3,17 @@
 //! This module provides a client for fetching GitHub organization projects
 //! and their associated data using both REST and GraphQL APIs.
 
+use crate::backend::BoardBackend;
+use crate::traffic;
 use anyhow::{Context as _, Result};
+use async_trait::async_trait;
 use backoff::{Error as BackoffError, ExponentialBackoff};
 use core::time::Duration;
 use log::{debug, error, info, trace, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
+use std::sync::OnceLock;
 
 /// Represents a GitHub organization
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,7 +36,7 @@ pub struct Project {
     /// Web URL to the project
     pub url: String,
     /// Project number within the organization
-    pub number: u32,
+    pub number: u64,
 }
 
 /// Represents a column in a project board
@@ -41,6 +45,7 @@ pub struct ProjectColumn {
     /// Column/option ID
     pub id: String,
     /// Number of items in this column
+    #[serde(default)]
     pub items_count: usize,
     /// Column name
     pub name: String,
@@ -54,13 +59,30 @@ pub struct ProjectItem {
     /// Item title
     pub title: String,
     /// List of assignee usernames
+    #[serde(default)]
     pub assignees: Vec<String>,
     /// ID of the column containing this item
     pub column_id: String,
     /// List of label names
+    #[serde(default)]
     pub labels: Vec<String>,
     /// Optional URL to the issue/PR
     pub url: Option<String>,
+    /// Last modification time of the project item, used as its version
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// GraphQL node ID of the underlying issue/PR
+    #[serde(default)]
+    pub content_id: Option<String>,
+}
+
+/// Current value of a single-select field on a project item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemFieldState {
+    /// Selected option ID, if the field is set
+    pub option_id: Option<String>,
+    /// Last modification time of the project item
+    pub updated_at: Option<String>,
 }
 
 /// Complete project data including columns and items
@@ -76,6 +98,74 @@ pub struct ProjectData {
     pub project: Project,
     /// GraphQL field ID for the status field
     pub status_field_id: String,
+    /// When this data was fetched from GitHub
+    #[serde(default)]
+    pub fetched_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether this data was served from the on-disk cache instead of GitHub
+    #[serde(default)]
+    pub stale: bool,
+}
+
+impl ProjectData {
+    /// Find an item by ID, issue or PR URL, or `#number`
+    pub fn find_item(&self, item: &str) -> Result<&ProjectItem> {
+        let number = item.trim_start_matches('#');
+        let is_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+        let matches: Vec<&ProjectItem> = self
+            .items
+            .iter()
+            .filter(|i| {
+                i.id == item
+                    || i.url.as_deref() == Some(item)
+                    || (is_number
+                        && i.url
+                            .as_deref()
+                            .and_then(|url| url.rsplit('/').next())
+                            .is_some_and(|n| n == number))
+            })
+            .collect();
+
+        match matches[..] {
+            [found] => Ok(found),
+            [] => anyhow::bail!("No item {item:?} on {}", self.project.title),
+            _ => anyhow::bail!(
+                "{item:?} matches several items, use one of: {}",
+                matches
+                    .iter()
+                    .filter_map(|i| i.url.as_deref())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// Find a column by name, ignoring case, or by ID
+    pub fn find_column(&self, column: &str) -> Result<&ProjectColumn> {
+        self.columns
+            .iter()
+            .find(|c| c.id == column || c.name.eq_ignore_ascii_case(column))
+            .with_context(|| {
+                let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+                format!("No column {column:?}, expected one of {}", names.join(", "))
+            })
+    }
+}
+
+/// Error raised when GitHub could not be reached at all
+#[derive(Debug)]
+pub struct NetworkError(pub String);
+
+impl std::fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+/// Check whether an error means GitHub was unreachable rather than refusing the request
+pub fn is_network_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<NetworkError>().is_some()
 }
 
 /// Type alias for column extraction result
@@ -86,13 +176,193 @@ type ColumnExtractResult = (
     HashMap<String, (String, String)>,
 );
 
+/// Page of project items with everything needed to show them on the board
+const ITEMS_PAGE_QUERY: &str = "
+query($projectId: ID!, $after: String) {
+    node(id: $projectId) {
+        ... on ProjectV2 {
+            items(first: 100, after: $after) {
+                pageInfo {
+                    hasNextPage
+                    endCursor
+                }
+                nodes {
+                    ...ItemFields
+                }
+            }
+        }
+    }
+}
+";
+
+/// Page of project item IDs and versions, used to find what changed
+const ITEM_VERSIONS_QUERY: &str = "
+query($projectId: ID!, $after: String) {
+    node(id: $projectId) {
+        ... on ProjectV2 {
+            items(first: 100, after: $after) {
+                pageInfo {
+                    hasNextPage
+                    endCursor
+                }
+                nodes {
+                    id
+                    updatedAt
+                    content {
+                        ... on Issue {
+                            updatedAt
+                        }
+                        ... on PullRequest {
+                            updatedAt
+                        }
+                        ... on DraftIssue {
+                            updatedAt
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+";
+
+/// Project item fields shown on the board
+const ITEM_FIELDS_FRAGMENT: &str = "
+fragment ItemFields on ProjectV2Item {
+    id
+    updatedAt
+    content {
+        ... on Issue {
+            id
+            title
+            url
+            assignees(first: 10) {
+                nodes {
+                    login
+                }
+            }
+            labels(first: 10) {
+                nodes {
+                    name
+                }
+            }
+        }
+        ... on PullRequest {
+            id
+            title
+            url
+            assignees(first: 10) {
+                nodes {
+                    login
+                }
+            }
+            labels(first: 10) {
+                nodes {
+                    name
+                }
+            }
+        }
+    }
+    fieldValues(first: 20) {
+        nodes {
+            ... on ProjectV2ItemFieldSingleSelectValue {
+                field {
+                    ... on ProjectV2SingleSelectField {
+                        id
+                    }
+                }
+                optionId
+            }
+        }
+    }
+}
+";
+
+/// Latest change to a project item or the issue/PR behind it
+struct ItemVersion {
+    id: String,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Recount the items in every column
+fn update_column_counts(columns: &mut [ProjectColumn], items: &[ProjectItem]) {
+    let mut column_counts: HashMap<&str, usize> = HashMap::new();
+    for item in items {
+        *column_counts.entry(item.column_id.as_str()).or_default() += 1;
+    }
+
+    for column in columns {
+        column.items_count = column_counts.get(column.id.as_str()).copied().unwrap_or(0);
+        debug!("Column '{}': {} items", column.name, column.items_count);
+    }
+}
+
+/// Environment variable pointing minik at another GitHub API, such as a local mock
+const API_URL_ENV: &str = "MINIK_GITHUB_API";
+
+/// Where the GitHub API is served
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    /// Base URL of the REST API
+    pub rest: String,
+    /// URL of the GraphQL API
+    pub graphql: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self::at("https://api.github.com")
+    }
+}
+
+impl Endpoints {
+    /// Endpoints of an API served at `base`, with GraphQL at `<base>/graphql`
+    pub fn at(base: &str) -> Self {
+        let rest = base.trim_end_matches('/').to_string();
+        Self {
+            graphql: format!("{rest}/graphql"),
+            rest,
+        }
+    }
+
+    /// Endpoints from `MINIK_GITHUB_API`, github.com when it is not set
+    pub fn from_env() -> Self {
+        std::env::var(API_URL_ENV)
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .map_or_else(Self::default, |url| Self::at(url.trim()))
+    }
+
+    /// Check whether the API is reachable, without any retries
+    pub async fn is_reachable(&self) -> bool {
+        if traffic::is_replaying() {
+            return true;
+        }
+        let result = reqwest::Client::new()
+            .head(&self.rest)
+            .header("User-Agent", "Minik-Kanban-App")
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                debug!("GitHub API is unreachable: {e}");
+                false
+            }
+        }
+    }
+}
+
 /// GitHub API client using authenticated requests
 pub struct GitHubClient {
     token: String,
+    endpoints: Endpoints,
 }
 
 /// Create an exponential backoff configuration with jitter
-fn create_backoff() -> ExponentialBackoff {
+pub(crate) fn create_backoff() -> ExponentialBackoff {
     ExponentialBackoff {
         initial_interval: Duration::from_millis(1000),
         randomization_factor: 0.5, // Add jitter
@@ -103,6 +373,35 @@ fn create_backoff() -> ExponentialBackoff {
     }
 }
 
+/// Decide whether a failed send is worth retrying
+fn send_error(err: anyhow::Error) -> BackoffError<anyhow::Error> {
+    if is_network_error(&err) {
+        BackoffError::transient(err)
+    } else {
+        BackoffError::permanent(err)
+    }
+}
+
+/// Check whether the GitHub API is reachable, without any retries
+pub async fn check_connectivity() -> bool {
+    Endpoints::from_env().is_reachable().await
+}
+
+/// gh account given with `--account`, `None` for gh's active account
+static ACCOUNT: OnceLock<String> = OnceLock::new();
+
+/// Use another gh account than the active one; only the first call counts
+pub fn set_account(login: String) {
+    if ACCOUNT.set(login).is_err() {
+        warn!("GitHub account already set, ignoring the new one");
+    }
+}
+
+/// Get the gh account chosen with `--account`
+pub fn account() -> Option<&'static str> {
+    ACCOUNT.get().map(String::as_str)
+}
+
 /// Find the gh CLI command in common locations
 fn find_gh_command() -> Result<String> {
     const POSSIBLE_PATHS: &[&str] = &[
@@ -133,13 +432,19 @@ fn find_gh_command() -> Result<String> {
 impl GitHubClient {
     /// Create a new GitHub client using the gh CLI authentication
     pub fn new() -> Result<Self> {
+        if traffic::is_replaying() {
+            debug!("Creating GitHub client for replayed traffic, without a token");
+            return Ok(Self::with_endpoints(String::new(), Endpoints::from_env()));
+        }
         debug!("Creating new GitHub client using gh CLI");
 
         let gh_path = find_gh_command()?;
-        let output = Command::new(&gh_path)
-            .args(["auth", "token"])
-            .output()
-            .context("Failed to execute gh command")?;
+        let mut command = Command::new(&gh_path);
+        command.args(["auth", "token"]);
+        if let Some(account) = account() {
+            command.args(["--user", account]);
+        }
+        let output = command.output().context("Failed to execute gh command")?;
 
         if !output.status.success() {
             error!(
@@ -159,7 +464,15 @@ impl GitHubClient {
             "GitHub client created successfully (token length: {})",
             token.len()
         );
-        Ok(Self { token })
+        Ok(Self::with_endpoints(token, Endpoints::from_env()))
+    }
+
+    /// Create a client for the API at `endpoints`, authenticating with `token`
+    pub fn with_endpoints(token: String, endpoints: Endpoints) -> Self {
+        if endpoints != Endpoints::default() {
+            debug!("Using GitHub API at {}", endpoints.rest);
+        }
+        Self { token, endpoints }
     }
 
     /// List all organizations the authenticated user belongs to
@@ -170,41 +483,35 @@ impl GitHubClient {
         let token = self.token.clone();
 
         let operation = || async {
-            let response = client
-                .get("https://api.github.com/user/orgs")
+            let request = client
+                .get(format!("{}/user/orgs", self.endpoints.rest))
                 .header("Authorization", format!("Bearer {token}"))
                 .header("User-Agent", "Minik-Kanban-App")
-                .timeout(Duration::from_secs(30))
-                .send()
-                .await
-                .map_err(|e| {
-                    warn!("Request failed: {e}");
-                    BackoffError::transient(anyhow::anyhow!("Request failed: {}", e))
-                })?;
-
-            let status = response.status();
+                .timeout(Duration::from_secs(30));
+            let (status, body) = traffic::send(request).await.map_err(|e| {
+                warn!("Request failed: {e}");
+                send_error(e)
+            })?;
 
             // Retry on rate limits or server errors
             if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
-                let error_body = response.text().await.unwrap_or_default();
-                warn!("GitHub API returned retryable status {status}: {error_body}");
+                warn!("GitHub API returned retryable status {status}: {body}");
                 return Err(BackoffError::transient(anyhow::anyhow!(
                     "Retryable status {}: {}",
                     status,
-                    error_body
+                    body
                 )));
             }
 
             if !status.is_success() {
-                let error_body = response.text().await.unwrap_or_default();
-                error!("GitHub API returned error status: {status} - {error_body}");
+                error!("GitHub API returned error status: {status} - {body}");
                 return Err(BackoffError::permanent(anyhow::anyhow!(
                     "Failed to fetch organizations: {}",
                     status
                 )));
             }
 
-            response.json::<Vec<Organization>>().await.map_err(|e| {
+            serde_json::from_str::<Vec<Organization>>(&body).map_err(|e| {
                 error!("Failed to parse organizations response: {e}");
                 BackoffError::permanent(e.into())
             })
@@ -254,7 +561,7 @@ impl GitHubClient {
                 Some(Project {
                     id: p["id"].as_str()?.to_string(),
                     title: p["title"].as_str()?.to_string(),
-                    number: u32::try_from(p["number"].as_u64()?).unwrap_or_default(),
+                    number: p["number"].as_u64()?,
                     url: p["url"].as_str()?.to_string(),
                 })
             })
@@ -275,6 +582,61 @@ impl GitHubClient {
         Ok(projects)
     }
 
+    /// Find an organization's project by its number
+    pub async fn project_by_number(&self, org: &str, number: u32) -> Result<Project> {
+        const QUERY: &str = "
+        query($org: String!, $number: Int!) {
+            organization(login: $org) {
+                projectV2(number: $number) {
+                    id
+                    title
+                    number
+                    url
+                }
+            }
+        }
+        ";
+
+        debug!("Looking up project {org}/{number}");
+        let variables = serde_json::json!({ "org": org, "number": number });
+        let response = self.graphql_request(QUERY, variables).await?;
+        let p = &response["data"]["organization"]["projectV2"];
+        if p.is_null() {
+            anyhow::bail!("Project {org}/{number} not found");
+        }
+
+        Ok(Project {
+            id: p["id"].as_str().unwrap_or_default().to_string(),
+            title: p["title"].as_str().unwrap_or_default().to_string(),
+            number: p["number"].as_u64().unwrap_or_default(),
+            url: p["url"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    /// Find the ID of a project given as `org/number`, or as a project ID
+    pub async fn resolve_project(&self, project: &str) -> Result<String> {
+        match project.split_once('/') {
+            Some((org, number)) => {
+                let number = number
+                    .parse()
+                    .with_context(|| format!("Invalid project number in {project:?}"))?;
+                Ok(self.project_by_number(org, number).await?.id)
+            }
+            None => Ok(project.to_string()),
+        }
+    }
+
+    /// Get the login of the authenticated user
+    pub async fn viewer_login(&self) -> Result<String> {
+        const QUERY: &str = "query { viewer { login } }";
+
+        let response = self.graphql_request(QUERY, serde_json::json!({})).await?;
+        response["data"]["viewer"]["login"]
+            .as_str()
+            .map(String::from)
+            .context("No login in viewer response")
+    }
+
     /// Get detailed data for a specific project
     pub async fn project_data(&self, project_id: &str) -> Result<ProjectData> {
         const QUERY: &str = "
@@ -301,59 +663,13 @@ impl GitHubClient {
                             }
                         }
                     }
-                    items(first: 100) {
-                        nodes {
-                            id
-                            content {
-                                ... on Issue {
-                                    title
-                                    url
-                                    assignees(first: 10) {
-                                        nodes {
-                                            login
-                                        }
-                                    }
-                                    labels(first: 10) {
-                                        nodes {
-                                            name
-                                        }
-                                    }
-                                }
-                                ... on PullRequest {
-                                    title
-                                    url
-                                    assignees(first: 10) {
-                                        nodes {
-                                            login
-                                        }
-                                    }
-                                    labels(first: 10) {
-                                        nodes {
-                                            name
-                                        }
-                                    }
-                                }
-                            }
-                            fieldValues(first: 20) {
-                                nodes {
-                                    ... on ProjectV2ItemFieldSingleSelectValue {
-                                        field {
-                                            ... on ProjectV2SingleSelectField {
-                                                id
-                                            }
-                                        }
-                                        optionId
-                                    }
-                                }
-                            }
-                        }
-                    }
                 }
             }
         }
         ";
 
         info!("Fetching detailed data for project ID: {project_id}");
+        let fetched_at = chrono::Utc::now();
         let variables = serde_json::json!({ "projectId": project_id });
         let response = self.graphql_request(QUERY, variables).await?;
         let project_node = &response["data"]["node"];
@@ -369,7 +685,7 @@ impl GitHubClient {
                 .as_str()
                 .unwrap_or_default()
                 .to_string(),
-            number: project_node["number"].as_u64().unwrap_or_default() as u32,
+            number: project_node["number"].as_u64().unwrap_or_default(),
             url: project_node["url"].as_str().unwrap_or_default().to_string(),
         };
 
@@ -378,15 +694,13 @@ impl GitHubClient {
             project.title, project.number, project.url
         );
 
-        let (columns, status_field_id, _column_map) = self.extract_columns(project_node)?;
-        let (items, column_counts) = self.extract_items(project_node)?;
+        let (mut columns, status_field_id, _column_map) = self.extract_columns(project_node)?;
 
-        // Update column item counts
-        let mut columns = columns;
-        for column in &mut columns {
-            column.items_count = column_counts.get(&column.id).copied().unwrap_or(0);
-            debug!("Column '{}': {} items", column.name, column.items_count);
-        }
+        let items_query = format!("{ITEMS_PAGE_QUERY}{ITEM_FIELDS_FRAGMENT}");
+        let item_nodes = self.paginate_items(&items_query, project_id).await?;
+        let items = self.extract_items(&item_nodes);
+
+        update_column_counts(&mut columns, &items);
 
         info!(
             "Successfully fetched project data: {} columns, {} items total",
@@ -400,9 +714,165 @@ impl GitHubClient {
             items,
             status_field_id,
             hidden_columns: Vec::new(), // Will be populated by the caller
+            fetched_at: Some(fetched_at),
+            stale: false,
+        })
+    }
+
+    /// Refresh a previously fetched board, only downloading items changed since then
+    ///
+    /// Item IDs and versions are listed cheaply, then full details are fetched only
+    /// for items updated since the last sync, newest first. Columns and project
+    /// metadata are reused from `previous`, so callers should still do a full
+    /// `project_data` now and then.
+    pub async fn refresh_project_data(&self, previous: &ProjectData) -> Result<ProjectData> {
+        let Some(last_sync) = previous.fetched_at else {
+            return self.project_data(&previous.project.id).await;
+        };
+
+        let project_id = &previous.project.id;
+        info!("Incremental refresh of project {project_id} since {last_sync}");
+        let fetched_at = chrono::Utc::now();
+
+        // Allow for clock skew between this machine and GitHub
+        let since = last_sync - chrono::Duration::minutes(1);
+        let versions = self.item_versions(project_id).await?;
+
+        let previous_items: HashMap<&str, &ProjectItem> = previous
+            .items
+            .iter()
+            .map(|item| (item.id.as_str(), item))
+            .collect();
+
+        let mut changed: Vec<&ItemVersion> = versions
+            .iter()
+            .filter(|v| v.updated_at > since || !previous_items.contains_key(v.id.as_str()))
+            .collect();
+        changed.sort_by_key(|v| std::cmp::Reverse(v.updated_at));
+
+        let changed_ids: Vec<&str> = changed.iter().map(|v| v.id.as_str()).collect();
+        let fetched: HashMap<String, ProjectItem> = self
+            .items_by_id(&changed_ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect();
+
+        // Keep the board order, using fresh data where we have it
+        let items: Vec<ProjectItem> = versions
+            .iter()
+            .filter_map(|v| {
+                fetched.get(&v.id).cloned().or_else(|| {
+                    previous_items
+                        .get(v.id.as_str())
+                        .map(|item| (*item).clone())
+                })
+            })
+            .collect();
+
+        let mut columns = previous.columns.clone();
+        update_column_counts(&mut columns, &items);
+
+        info!(
+            "Incremental refresh of project {project_id}: {} items, {} changed",
+            items.len(),
+            fetched.len()
+        );
+
+        Ok(ProjectData {
+            project: previous.project.clone(),
+            columns,
+            items,
+            status_field_id: previous.status_field_id.clone(),
+            hidden_columns: Vec::new(), // Will be populated by the caller
+            fetched_at: Some(fetched_at),
+            stale: false,
         })
     }
 
+    /// List the ID and version of every item in a project, without any details
+    async fn item_versions(&self, project_id: &str) -> Result<Vec<ItemVersion>> {
+        let nodes = self.paginate_items(ITEM_VERSIONS_QUERY, project_id).await?;
+
+        let versions = nodes
+            .iter()
+            .filter_map(|node| {
+                let id = node["id"].as_str()?.to_string();
+                // Issue edits only bump the content's updatedAt, field changes the item's
+                let updated_at = [&node["updatedAt"], &node["content"]["updatedAt"]]
+                    .into_iter()
+                    .filter_map(|v| v.as_str())
+                    .filter_map(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+                    .map(|v| v.with_timezone(&chrono::Utc))
+                    .max()
+                    .unwrap_or_else(chrono::Utc::now);
+                Some(ItemVersion { id, updated_at })
+            })
+            .collect::<Vec<_>>();
+
+        debug!("Listed {} item versions for {project_id}", versions.len());
+        Ok(versions)
+    }
+
+    /// Fetch full details of specific project items
+    async fn items_by_id(&self, item_ids: &[&str]) -> Result<Vec<ProjectItem>> {
+        const QUERY: &str = "
+        query($ids: [ID!]!) {
+            nodes(ids: $ids) {
+                ...ItemFields
+            }
+        }
+        ";
+
+        let query = format!("{QUERY}{ITEM_FIELDS_FRAGMENT}");
+        let mut items = Vec::with_capacity(item_ids.len());
+        for chunk in item_ids.chunks(100) {
+            debug!("Fetching details of {} changed items", chunk.len());
+            let variables = serde_json::json!({ "ids": chunk });
+            let response = self.graphql_request(&query, variables).await?;
+            let nodes = response["data"]["nodes"]
+                .as_array()
+                .context("Failed to parse items array")?;
+            items.extend(self.extract_items(nodes));
+        }
+
+        Ok(items)
+    }
+
+    /// Fetch every page of a project's items connection
+    ///
+    /// The query must take `$projectId` and `$after` and select
+    /// `items(first: 100, after: $after)` with `pageInfo` on the project node.
+    async fn paginate_items(
+        &self,
+        query: &str,
+        project_id: &str,
+    ) -> Result<Vec<serde_json::Value>> {
+        const MAX_PAGES: usize = 50;
+
+        let mut nodes = Vec::new();
+        let mut after: Option<String> = None;
+
+        for page in 1..=MAX_PAGES {
+            let variables = serde_json::json!({ "projectId": project_id, "after": after });
+            let response = self.graphql_request(query, variables).await?;
+            let items = &response["data"]["node"]["items"];
+
+            if let Some(page_nodes) = items["nodes"].as_array() {
+                debug!("Fetched page {page} with {} items", page_nodes.len());
+                nodes.extend(page_nodes.iter().cloned());
+            }
+
+            if !items["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+                return Ok(nodes);
+            }
+            after = items["pageInfo"]["endCursor"].as_str().map(String::from);
+        }
+
+        warn!("Project {project_id} has more than {MAX_PAGES} pages of items, truncating");
+        Ok(nodes)
+    }
+
     /// Extract columns from project node response
     /// Extract columns from project data
     fn extract_columns(&self, project_node: &serde_json::Value) -> Result<ColumnExtractResult> {
@@ -453,78 +923,130 @@ impl GitHubClient {
         Ok((columns, status_field_id, column_map))
     }
 
-    /// Extract items from project node response
-    fn extract_items(
-        &self,
-        project_node: &serde_json::Value,
-    ) -> Result<(Vec<ProjectItem>, HashMap<String, usize>)> {
+    /// Extract items from a list of project item nodes
+    fn extract_items(&self, items_nodes: &[serde_json::Value]) -> Vec<ProjectItem> {
         let mut items = Vec::new();
-        let mut column_counts: HashMap<String, usize> = HashMap::new();
+        debug!("Processing {} project items", items_nodes.len());
 
-        if let Some(items_nodes) = project_node["items"]["nodes"].as_array() {
-            debug!("Processing {} project items", items_nodes.len());
+        for item in items_nodes {
+            let content = &item["content"];
+            if content.is_null() {
+                trace!("Skipping item with null content");
+                continue;
+            }
+
+            let title = content["title"].as_str().unwrap_or("Untitled").to_string();
+            let url = content["url"].as_str().map(String::from);
 
-            for item in items_nodes {
-                let content = &item["content"];
-                if content.is_null() {
-                    trace!("Skipping item with null content");
-                    continue;
+            let assignees = content["assignees"]["nodes"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|a| a["login"].as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let labels = content["labels"]["nodes"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|l| l["name"].as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut column_id = String::new();
+            if let Some(field_values) = item["fieldValues"]["nodes"].as_array() {
+                for fv in field_values {
+                    if let Some(option_id) = fv["optionId"].as_str() {
+                        column_id = option_id.to_string();
+                        break;
+                    }
                 }
+            }
+
+            items.push(ProjectItem {
+                id: item["id"].as_str().unwrap_or_default().to_string(),
+                title,
+                url,
+                assignees,
+                labels,
+                column_id,
+                updated_at: item["updatedAt"].as_str().map(String::from),
+                content_id: content["id"].as_str().map(String::from),
+            });
+        }
 
-                let title = content["title"].as_str().unwrap_or("Untitled").to_string();
-                let url = content["url"].as_str().map(String::from);
-
-                let assignees = content["assignees"]["nodes"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|a| a["login"].as_str().map(String::from))
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
-                let labels = content["labels"]["nodes"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|l| l["name"].as_str().map(String::from))
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
-                let mut column_id = String::new();
-                if let Some(field_values) = item["fieldValues"]["nodes"].as_array() {
-                    for fv in field_values {
-                        if let Some(option_id) = fv["optionId"].as_str() {
-                            column_id = option_id.to_string();
-                            *column_counts.entry(option_id.to_string()).or_default() += 1;
-                            break;
+        items
+    }
+
+    /// Get the single-select option an item currently has for a field, plus its version
+    pub async fn item_field_state(&self, item_id: &str, field_id: &str) -> Result<ItemFieldState> {
+        const QUERY: &str = "
+        query($itemId: ID!) {
+            node(id: $itemId) {
+                ... on ProjectV2Item {
+                    id
+                    updatedAt
+                    fieldValues(first: 20) {
+                        nodes {
+                            ... on ProjectV2ItemFieldSingleSelectValue {
+                                field {
+                                    ... on ProjectV2SingleSelectField {
+                                        id
+                                    }
+                                }
+                                optionId
+                            }
                         }
                     }
                 }
-
-                items.push(ProjectItem {
-                    id: item["id"].as_str().unwrap_or_default().to_string(),
-                    title,
-                    url,
-                    assignees,
-                    labels,
-                    column_id,
-                });
             }
         }
+        ";
 
-        Ok((items, column_counts))
+        debug!("Fetching field {field_id} of item {item_id}");
+        let variables = serde_json::json!({ "itemId": item_id });
+        let response = self.graphql_request(QUERY, variables).await?;
+        let item_node = &response["data"]["node"];
+
+        if item_node.is_null() {
+            error!("Project item not found for ID: {item_id}");
+            anyhow::bail!("Project item {item_id} no longer exists");
+        }
+
+        let option_id = item_node["fieldValues"]["nodes"]
+            .as_array()
+            .and_then(|values| {
+                values
+                    .iter()
+                    .find(|fv| fv["field"]["id"].as_str() == Some(field_id))
+            })
+            .and_then(|fv| fv["optionId"].as_str())
+            .map(String::from);
+
+        let updated_at = item_node["updatedAt"].as_str().map(String::from);
+
+        debug!(
+            "Item {item_id} has option {option_id:?} for field {field_id} (updated {updated_at:?})"
+        );
+        Ok(ItemFieldState {
+            option_id,
+            updated_at,
+        })
     }
 
     /// Update a project item's field value
+    ///
+    /// Returns the item's new `updatedAt` version.
     pub async fn update_item_field(
         &self,
         project_id: &str,
         item_id: &str,
         field_id: &str,
         option_id: &str,
-    ) -> Result<()> {
+    ) -> Result<Option<String>> {
         const MUTATION: &str = "
         mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $value: ProjectV2FieldValue!) {
             updateProjectV2ItemFieldValue(input: {
@@ -535,6 +1057,7 @@ impl GitHubClient {
             }) {
                 projectV2Item {
                     id
+                    updatedAt
                 }
             }
         }
@@ -582,9 +1105,201 @@ impl GitHubClient {
         }
 
         info!("✅ Successfully updated item to new column!");
+        Ok(
+            response["data"]["updateProjectV2ItemFieldValue"]["projectV2Item"]["updatedAt"]
+                .as_str()
+                .map(String::from),
+        )
+    }
+
+    /// Clear a project item's field value
+    pub async fn clear_item_field(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        field_id: &str,
+    ) -> Result<()> {
+        const MUTATION: &str = "
+        mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!) {
+            clearProjectV2ItemFieldValue(input: {
+                projectId: $projectId
+                itemId: $itemId
+                fieldId: $fieldId
+            }) {
+                projectV2Item {
+                    id
+                }
+            }
+        }
+        ";
+
+        info!("Clearing field {field_id} of item {item_id}");
+        let variables = serde_json::json!({
+            "projectId": project_id,
+            "itemId": item_id,
+            "fieldId": field_id,
+        });
+        self.mutate("clearProjectV2ItemFieldValue", MUTATION, variables)
+            .await?;
+        Ok(())
+    }
+
+    /// Archive or unarchive a project item
+    pub async fn set_item_archived(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        archived: bool,
+    ) -> Result<()> {
+        const ARCHIVE: &str = "
+        mutation($projectId: ID!, $itemId: ID!) {
+            archiveProjectV2Item(input: { projectId: $projectId, itemId: $itemId }) {
+                item {
+                    id
+                }
+            }
+        }
+        ";
+        const UNARCHIVE: &str = "
+        mutation($projectId: ID!, $itemId: ID!) {
+            unarchiveProjectV2Item(input: { projectId: $projectId, itemId: $itemId }) {
+                item {
+                    id
+                }
+            }
+        }
+        ";
+
+        info!("Setting archived={archived} on item {item_id}");
+        let variables = serde_json::json!({ "projectId": project_id, "itemId": item_id });
+        if archived {
+            self.mutate("archiveProjectV2Item", ARCHIVE, variables)
+                .await?;
+        } else {
+            self.mutate("unarchiveProjectV2Item", UNARCHIVE, variables)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Get the GraphQL node ID of the issue/PR behind a project item
+    pub async fn item_content_id(&self, item_id: &str) -> Result<String> {
+        const QUERY: &str = "
+        query($itemId: ID!) {
+            node(id: $itemId) {
+                ... on ProjectV2Item {
+                    content {
+                        ... on Issue {
+                            id
+                        }
+                        ... on PullRequest {
+                            id
+                        }
+                    }
+                }
+            }
+        }
+        ";
+
+        let variables = serde_json::json!({ "itemId": item_id });
+        let response = self.graphql_request(QUERY, variables).await?;
+        response["data"]["node"]["content"]["id"]
+            .as_str()
+            .map(String::from)
+            .with_context(|| format!("Project item {item_id} has no issue or pull request"))
+    }
+
+    /// Comment on an issue/PR, returning the URL of the new comment
+    pub async fn add_comment(&self, content_id: &str, body: &str) -> Result<String> {
+        const MUTATION: &str = "
+        mutation($subjectId: ID!, $body: String!) {
+            addComment(input: { subjectId: $subjectId, body: $body }) {
+                commentEdge {
+                    node {
+                        url
+                    }
+                }
+            }
+        }
+        ";
+
+        info!("Adding comment to {content_id}");
+        let variables = serde_json::json!({ "subjectId": content_id, "body": body });
+        let response = self.mutate("addComment", MUTATION, variables).await?;
+        Ok(response["data"]["addComment"]["commentEdge"]["node"]["url"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Assign a user to an issue/PR, or remove their assignment
+    pub async fn set_assigned(&self, content_id: &str, login: &str, assigned: bool) -> Result<()> {
+        const USER_QUERY: &str = "
+        query($login: String!) {
+            user(login: $login) {
+                id
+            }
+        }
+        ";
+        const ADD: &str = "
+        mutation($assignableId: ID!, $assigneeIds: [ID!]!) {
+            addAssigneesToAssignable(input: { assignableId: $assignableId, assigneeIds: $assigneeIds }) {
+                assignable {
+                    __typename
+                }
+            }
+        }
+        ";
+        const REMOVE: &str = "
+        mutation($assignableId: ID!, $assigneeIds: [ID!]!) {
+            removeAssigneesFromAssignable(input: { assignableId: $assignableId, assigneeIds: $assigneeIds }) {
+                assignable {
+                    __typename
+                }
+            }
+        }
+        ";
+
+        let response = self
+            .graphql_request(USER_QUERY, serde_json::json!({ "login": login }))
+            .await?;
+        let user_id = response["data"]["user"]["id"]
+            .as_str()
+            .with_context(|| format!("GitHub user {login} not found"))?;
+
+        info!("Setting assigned={assigned} for {login} on {content_id}");
+        let variables = serde_json::json!({
+            "assignableId": content_id,
+            "assigneeIds": [user_id],
+        });
+        if assigned {
+            self.mutate("addAssigneesToAssignable", ADD, variables)
+                .await?;
+        } else {
+            self.mutate("removeAssigneesFromAssignable", REMOVE, variables)
+                .await?;
+        }
         Ok(())
     }
 
+    /// Run a GraphQL mutation and make sure it returned a payload
+    async fn mutate(
+        &self,
+        name: &str,
+        mutation: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let response = self.graphql_request(mutation, variables).await?;
+
+        if response["data"][name].is_null() {
+            error!("Mutation {name} returned no payload: {response:?}");
+            anyhow::bail!("Mutation {name} failed - no payload in response");
+        }
+
+        debug!("Mutation {name} succeeded");
+        Ok(response)
+    }
+
     /// Execute a GraphQL request
     async fn graphql_request(
         &self,
@@ -592,7 +1307,7 @@ impl GitHubClient {
         variables: serde_json::Value,
     ) -> Result<serde_json::Value> {
         info!("🌐 ========== GRAPHQL REQUEST ==========");
-        info!("📍 Endpoint: https://api.github.com/graphql");
+        info!("📍 Endpoint: {}", self.endpoints.graphql);
         info!(
             "🔑 Token present: {} (length: {})",
             !self.token.is_empty(),
@@ -617,43 +1332,37 @@ impl GitHubClient {
         let operation = || async {
             info!("🚀 Sending HTTP POST request to GitHub GraphQL API...");
 
-            let response = client
-                .post("https://api.github.com/graphql")
+            let request = client
+                .post(&self.endpoints.graphql)
                 .header("Authorization", format!("Bearer {token}"))
                 .header("User-Agent", "Minik-Kanban-App")
                 .json(&request_body)
-                .timeout(Duration::from_secs(30))
-                .send()
-                .await
-                .map_err(|e| {
-                    warn!("GraphQL request failed: {e}");
-                    BackoffError::transient(anyhow::anyhow!("GraphQL request failed: {}", e))
-                })?;
-
-            let status = response.status();
+                .timeout(Duration::from_secs(30));
+            let (status, body) = traffic::send(request).await.map_err(|e| {
+                warn!("GraphQL request failed: {e}");
+                send_error(e)
+            })?;
             info!("📨 Response received! Status: {status}");
 
             // Retry on rate limits or server errors
             if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
-                let error_text = response.text().await.unwrap_or_default();
-                warn!("GraphQL returned retryable status {status}: {error_text}");
+                warn!("GraphQL returned retryable status {status}: {body}");
                 return Err(BackoffError::transient(anyhow::anyhow!(
                     "Retryable GraphQL status {}: {}",
                     status,
-                    error_text
+                    body
                 )));
             }
 
             if !status.is_success() {
-                let error_text = response.text().await.unwrap_or_default();
-                error!("GraphQL request failed with status {status}: {error_text}");
+                error!("GraphQL request failed with status {status}: {body}");
                 return Err(BackoffError::permanent(anyhow::anyhow!(
                     "GraphQL request failed: {}",
-                    error_text
+                    body
                 )));
             }
 
-            let data: serde_json::Value = response.json().await.map_err(|e| {
+            let data: serde_json::Value = serde_json::from_str(&body).map_err(|e| {
                 error!("Failed to parse GraphQL response: {e}");
                 BackoffError::permanent(e.into())
             })?;
@@ -689,3 +1398,79 @@ impl GitHubClient {
         Ok(data)
     }
 }
+
+#[async_trait]
+impl BoardBackend for GitHubClient {
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    async fn is_reachable(&self) -> bool {
+        self.endpoints.is_reachable().await
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>> {
+        GitHubClient::list_organizations(self).await
+    }
+
+    async fn list_projects(&self, org: &str) -> Result<Vec<Project>> {
+        self.list_org_projects(org).await
+    }
+
+    async fn project_data(&self, project_id: &str) -> Result<ProjectData> {
+        GitHubClient::project_data(self, project_id).await
+    }
+
+    async fn refresh_project_data(&self, previous: &ProjectData) -> Result<ProjectData> {
+        GitHubClient::refresh_project_data(self, previous).await
+    }
+
+    async fn item_field_state(&self, item_id: &str, field_id: &str) -> Result<ItemFieldState> {
+        GitHubClient::item_field_state(self, item_id, field_id).await
+    }
+
+    async fn move_item(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        field_id: &str,
+        column_id: &str,
+    ) -> Result<Option<String>> {
+        self.update_item_field(project_id, item_id, field_id, column_id)
+            .await
+    }
+
+    async fn set_item_field(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        field_id: &str,
+        option_id: Option<&str>,
+    ) -> Result<()> {
+        match option_id {
+            Some(option_id) => {
+                self.update_item_field(project_id, item_id, field_id, option_id)
+                    .await?;
+            }
+            None => self.clear_item_field(project_id, item_id, field_id).await?,
+        }
+        Ok(())
+    }
+
+    async fn item_content_id(&self, item_id: &str) -> Result<String> {
+        GitHubClient::item_content_id(self, item_id).await
+    }
+
+    async fn set_assigned(&self, content_id: &str, login: &str, assigned: bool) -> Result<()> {
+        GitHubClient::set_assigned(self, content_id, login, assigned).await
+    }
+
+    async fn set_item_archived(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        archived: bool,
+    ) -> Result<()> {
+        GitHubClient::set_item_archived(self, project_id, item_id, archived).await
+    }
+}
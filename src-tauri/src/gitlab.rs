@@ -0,0 +1,524 @@
+//! GitLab issue boards
+//!
+//! Reads group and project boards from the GitLab REST API with a personal
+//! access token. A board's label lists become columns, framed by the Open and
+//! Closed lists GitLab always shows. Moving a card swaps the issue's list
+//! labels, and closes or reopens it for the Closed list.
+
+use crate::backend::BoardBackend;
+use crate::config::GitLabConfig;
+use crate::github::{
+    ItemFieldState, Organization, Project, ProjectColumn, ProjectData, ProjectItem,
+};
+use crate::rest::{self, RestClient};
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{info, warn};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// Prefix of the organization and project IDs of GitLab boards
+pub const PREFIX: &str = "gitlab";
+
+/// Environment variable holding the access token when config.toml has none
+const TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+/// Column of open issues without a list label
+const OPEN_COLUMN: &str = "open";
+
+/// Column of closed issues
+const CLOSED_COLUMN: &str = "closed";
+
+/// Closed issues shown on a board, most recently updated first
+const CLOSED_LIMIT: u32 = 20;
+
+/// Where a board lives
+#[derive(Debug, Clone, Copy)]
+enum Scope {
+    Group(u64),
+    Project(u64),
+}
+
+impl Scope {
+    fn segments(self) -> [String; 2] {
+        match self {
+            Self::Group(id) => ["groups".to_string(), id.to_string()],
+            Self::Project(id) => ["projects".to_string(), id.to_string()],
+        }
+    }
+}
+
+/// A board as identified by its project ID, `gitlab:group/<id>/<board>` or `gitlab:project/<id>/<board>`
+#[derive(Debug, Clone, Copy)]
+struct BoardRef {
+    scope: Scope,
+    board: u64,
+}
+
+impl BoardRef {
+    fn parse(project_id: &str) -> Result<Self> {
+        let invalid = || format!("{project_id:?} is not a GitLab board");
+        let rest = project_id
+            .strip_prefix(PREFIX)
+            .and_then(|r| r.strip_prefix(':'))
+            .with_context(invalid)?;
+        let parts: Vec<&str> = rest.split('/').collect();
+        let [kind, id, board] = parts[..] else {
+            anyhow::bail!(invalid());
+        };
+        let id = id.parse().with_context(invalid)?;
+        let scope = match kind {
+            "group" => Scope::Group(id),
+            "project" => Scope::Project(id),
+            _ => anyhow::bail!(invalid()),
+        };
+        Ok(Self {
+            scope,
+            board: board.parse().with_context(invalid)?,
+        })
+    }
+
+    fn project_id(self) -> String {
+        let [kind, id] = self.scope.segments();
+        format!(
+            "{PREFIX}:{}/{id}/{}",
+            kind.trim_end_matches('s'),
+            self.board
+        )
+    }
+}
+
+/// An issue as identified by its item ID, `<project id>/<iid>`
+fn parse_item_id(item_id: &str) -> Result<(u64, u64)> {
+    item_id
+        .split_once('/')
+        .and_then(|(project, iid)| Some((project.parse().ok()?, iid.parse().ok()?)))
+        .with_context(|| format!("{item_id:?} is not a GitLab issue"))
+}
+
+/// Get the column an issue sits in, given the labels of the board's lists in order
+fn issue_column(issue: &Value, list_labels: &[String]) -> String {
+    if issue["state"].as_str() == Some("closed") {
+        return CLOSED_COLUMN.to_string();
+    }
+    let labels = strings(&issue["labels"]);
+    list_labels
+        .iter()
+        .find(|label| labels.contains(label))
+        .map_or_else(|| OPEN_COLUMN.to_string(), |label| label_column(label))
+}
+
+fn label_column(label: &str) -> String {
+    format!("label:{label}")
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn issue_item(issue: &Value, list_labels: &[String]) -> ProjectItem {
+    ProjectItem {
+        id: format!("{}/{}", issue["project_id"], issue["iid"]),
+        title: issue["title"].as_str().unwrap_or_default().to_string(),
+        assignees: issue["assignees"]
+            .as_array()
+            .map(|users| {
+                users
+                    .iter()
+                    .filter_map(|u| u["username"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        column_id: issue_column(issue, list_labels),
+        labels: strings(&issue["labels"]),
+        url: issue["web_url"].as_str().map(String::from),
+        updated_at: issue["updated_at"].as_str().map(String::from),
+        content_id: None,
+    }
+}
+
+/// A board's name, label lists and issue filters
+struct Board {
+    name: String,
+    list_labels: Vec<String>,
+    /// Query narrowing the issues to the board's scope
+    filters: Vec<(&'static str, String)>,
+}
+
+/// Client for the GitLab REST API
+pub struct GitLabClient {
+    rest: RestClient,
+    groups: Vec<String>,
+}
+
+impl GitLabClient {
+    /// Create a client from the `[gitlab]` section of the config
+    pub fn new(config: &GitLabConfig) -> Result<Self> {
+        let token = rest::token("GitLab", config.token.as_deref(), TOKEN_ENV)?;
+        Ok(Self {
+            rest: RestClient::new(
+                "GitLab",
+                &config.url,
+                &["api", "v4"],
+                ("PRIVATE-TOKEN", token),
+            )?,
+            groups: config.groups.clone(),
+        })
+    }
+
+    async fn get<S: AsRef<str>>(&self, segments: &[S], query: &[(&str, String)]) -> Result<Value> {
+        self.rest.get(segments, query).await
+    }
+
+    /// Get every page of a list
+    async fn get_all<S: AsRef<str>>(
+        &self,
+        segments: &[S],
+        query: &[(&str, String)],
+    ) -> Result<Vec<Value>> {
+        let mut results = Vec::new();
+        let mut page = Some("1".to_string());
+        while let Some(current) = page {
+            let mut paged = query.to_vec();
+            paged.push(("per_page", "100".to_string()));
+            paged.push(("page", current));
+            let (data, headers) = self
+                .rest
+                .request(Method::GET, self.rest.url(segments), &paged, None)
+                .await?;
+            results.extend(data.as_array().cloned().unwrap_or_default());
+            page = rest::header(&headers, "x-next-page").map(String::from);
+        }
+        Ok(results)
+    }
+
+    /// Get a board's name, label lists in order and issue filters
+    async fn board(&self, board: BoardRef) -> Result<Board> {
+        let [kind, id] = board.scope.segments();
+        let data = self
+            .get(
+                &[kind, id, "boards".to_string(), board.board.to_string()],
+                &[],
+            )
+            .await?;
+
+        let mut lists: Vec<&Value> = data["lists"]
+            .as_array()
+            .map(|lists| lists.iter().filter(|l| l["label"].is_object()).collect())
+            .unwrap_or_default();
+        lists.sort_by_key(|l| l["position"].as_i64().unwrap_or_default());
+
+        let mut filters = Vec::new();
+        let scope_labels: Vec<&str> = data["labels"]
+            .as_array()
+            .map(|labels| labels.iter().filter_map(|l| l["name"].as_str()).collect())
+            .unwrap_or_default();
+        if !scope_labels.is_empty() {
+            filters.push(("labels", scope_labels.join(",")));
+        }
+        if let Some(milestone) = data["milestone"]["title"].as_str() {
+            filters.push(("milestone", milestone.to_string()));
+        }
+
+        Ok(Board {
+            name: data["name"].as_str().unwrap_or("Board").to_string(),
+            list_labels: lists
+                .iter()
+                .filter_map(|l| l["label"]["name"].as_str().map(String::from))
+                .collect(),
+            filters,
+        })
+    }
+
+    /// List the boards of a group's projects
+    async fn project_boards(&self, group: &str) -> Result<Vec<Project>> {
+        let projects = self
+            .get_all(
+                &["groups", group, "projects"],
+                &[
+                    ("archived", "false".to_string()),
+                    ("with_issues_enabled", "true".to_string()),
+                ],
+            )
+            .await?;
+
+        let results = join_all(projects.iter().map(|project| async move {
+            let id = project["id"].as_u64().unwrap_or_default();
+            let boards = self
+                .get(
+                    &["projects".to_string(), id.to_string(), "boards".to_string()],
+                    &[],
+                )
+                .await;
+            (project, id, boards)
+        }))
+        .await;
+
+        let mut boards = Vec::new();
+        for (project, id, result) in results {
+            let name = project["name"].as_str().unwrap_or_default();
+            let web_url = project["web_url"].as_str().unwrap_or_default();
+            match result {
+                Ok(data) => {
+                    for board in data.as_array().into_iter().flatten() {
+                        let board_id = board["id"].as_u64().unwrap_or_default();
+                        boards.push(Project {
+                            id: BoardRef {
+                                scope: Scope::Project(id),
+                                board: board_id,
+                            }
+                            .project_id(),
+                            title: format!(
+                                "{name} / {}",
+                                board["name"].as_str().unwrap_or("Board")
+                            ),
+                            url: format!("{web_url}/-/boards/{board_id}"),
+                            number: board_id,
+                        });
+                    }
+                }
+                Err(e) => warn!("Failed to list boards of GitLab project {name}: {e}"),
+            }
+        }
+        Ok(boards)
+    }
+}
+
+#[async_trait]
+impl BoardBackend for GitLabClient {
+    fn name(&self) -> &str {
+        "GitLab"
+    }
+
+    async fn is_reachable(&self) -> bool {
+        self.rest.is_reachable().await
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>> {
+        let groups = if self.groups.is_empty() {
+            self.get_all(&["groups"], &[("min_access_level", "10".to_string())])
+                .await?
+        } else {
+            let mut groups = Vec::new();
+            for group in &self.groups {
+                groups.push(
+                    self.get(
+                        &["groups", group.as_str()],
+                        &[("with_projects", "false".to_string())],
+                    )
+                    .await?,
+                );
+            }
+            groups
+        };
+
+        let organizations: Vec<Organization> = groups
+            .iter()
+            .map(|group| Organization {
+                id: group["id"].as_u64().unwrap_or_default(),
+                login: format!(
+                    "{PREFIX}:{}",
+                    group["full_path"].as_str().unwrap_or_default()
+                ),
+                name: group["full_name"].as_str().map(String::from),
+            })
+            .collect();
+        info!("Found {} GitLab groups", organizations.len());
+        Ok(organizations)
+    }
+
+    async fn list_projects(&self, org: &str) -> Result<Vec<Project>> {
+        let group = org
+            .strip_prefix(PREFIX)
+            .and_then(|g| g.strip_prefix(':'))
+            .with_context(|| format!("{org:?} is not a GitLab group"))?;
+
+        let group_path = ["groups", group];
+        let boards_path = ["groups", group, "boards"];
+        let group_query = [("with_projects", "false".to_string())];
+        let (data, boards) = futures::try_join!(
+            self.get(&group_path, &group_query),
+            self.get(&boards_path, &[]),
+        )?;
+        let group_id = data["id"].as_u64().unwrap_or_default();
+        let web_url = data["web_url"].as_str().unwrap_or_default();
+
+        let mut projects: Vec<Project> = boards
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|board| {
+                let board_id = board["id"].as_u64().unwrap_or_default();
+                Project {
+                    id: BoardRef {
+                        scope: Scope::Group(group_id),
+                        board: board_id,
+                    }
+                    .project_id(),
+                    title: board["name"].as_str().unwrap_or("Board").to_string(),
+                    url: format!("{web_url}/-/boards/{board_id}"),
+                    number: board_id,
+                }
+            })
+            .collect();
+        projects.extend(self.project_boards(group).await?);
+        Ok(projects)
+    }
+
+    async fn project_data(&self, project_id: &str) -> Result<ProjectData> {
+        let board_ref = BoardRef::parse(project_id)?;
+        let [kind, id] = board_ref.scope.segments();
+        let board = self.board(board_ref).await?;
+
+        let issues_path = [kind.clone(), id.clone(), "issues".to_string()];
+        let mut open_query = board.filters.clone();
+        open_query.push(("state", "opened".to_string()));
+        let mut closed_query = board.filters.clone();
+        closed_query.extend([
+            ("state", "closed".to_string()),
+            ("order_by", "updated_at".to_string()),
+            ("per_page", CLOSED_LIMIT.to_string()),
+        ]);
+
+        let owner_path = [kind, id];
+        let (owner, open, closed) = futures::try_join!(
+            self.get(&owner_path, &[]),
+            self.get_all(&issues_path, &open_query),
+            self.get(&issues_path, &closed_query),
+        )?;
+
+        let items: Vec<ProjectItem> = open
+            .iter()
+            .chain(closed.as_array().into_iter().flatten())
+            .map(|issue| issue_item(issue, &board.list_labels))
+            .collect();
+
+        let mut columns = vec![ProjectColumn {
+            id: OPEN_COLUMN.to_string(),
+            items_count: 0,
+            name: "Open".to_string(),
+        }];
+        columns.extend(board.list_labels.iter().map(|label| ProjectColumn {
+            id: label_column(label),
+            items_count: 0,
+            name: label.clone(),
+        }));
+        columns.push(ProjectColumn {
+            id: CLOSED_COLUMN.to_string(),
+            items_count: 0,
+            name: "Closed".to_string(),
+        });
+        for column in &mut columns {
+            column.items_count = items.iter().filter(|i| i.column_id == column.id).count();
+        }
+
+        let title = match board_ref.scope {
+            Scope::Group(_) => board.name,
+            Scope::Project(_) => format!(
+                "{} / {}",
+                owner["name"].as_str().unwrap_or_default(),
+                board.name
+            ),
+        };
+        info!("Fetched GitLab board {title:?} with {} items", items.len());
+
+        Ok(ProjectData {
+            project: Project {
+                id: project_id.to_string(),
+                title,
+                url: format!(
+                    "{}/-/boards/{}",
+                    owner["web_url"].as_str().unwrap_or_default(),
+                    board_ref.board
+                ),
+                number: board_ref.board,
+            },
+            columns,
+            hidden_columns: Vec::new(),
+            items,
+            // The board decides which labels are columns, so it stands in for the status field
+            status_field_id: project_id.to_string(),
+            fetched_at: Some(chrono::Utc::now()),
+            stale: false,
+        })
+    }
+
+    async fn item_field_state(&self, item_id: &str, field_id: &str) -> Result<ItemFieldState> {
+        let (project, iid) = parse_item_id(item_id)?;
+        let board = self.board(BoardRef::parse(field_id)?).await?;
+        let issue = self
+            .get(
+                &[
+                    "projects".to_string(),
+                    project.to_string(),
+                    "issues".to_string(),
+                    iid.to_string(),
+                ],
+                &[],
+            )
+            .await?;
+
+        Ok(ItemFieldState {
+            option_id: Some(issue_column(&issue, &board.list_labels)),
+            updated_at: issue["updated_at"].as_str().map(String::from),
+        })
+    }
+
+    async fn move_item(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        _field_id: &str,
+        column_id: &str,
+    ) -> Result<Option<String>> {
+        let (project, iid) = parse_item_id(item_id)?;
+        let board = self.board(BoardRef::parse(project_id)?).await?;
+
+        // Swap the list labels, keeping every other label of the issue
+        let target = column_id.strip_prefix("label:");
+        if let Some(label) = target {
+            if !board.list_labels.iter().any(|l| l == label) {
+                anyhow::bail!("No list {label:?} on this board");
+            }
+        } else if column_id != OPEN_COLUMN && column_id != CLOSED_COLUMN {
+            anyhow::bail!("No column {column_id:?} on this board");
+        }
+        let remove: Vec<&str> = board
+            .list_labels
+            .iter()
+            .map(String::as_str)
+            .filter(|l| Some(*l) != target)
+            .collect();
+
+        // Arrays rather than comma-separated strings, label names may contain commas
+        let mut body = json!({
+            "remove_labels": remove,
+            "state_event": if column_id == CLOSED_COLUMN { "close" } else { "reopen" },
+        });
+        if let Some(label) = target {
+            body["add_labels"] = json!([label]);
+        }
+
+        info!("Moving GitLab issue {item_id} to {column_id}");
+        let url = self.rest.url(&[
+            "projects".to_string(),
+            project.to_string(),
+            "issues".to_string(),
+            iid.to_string(),
+        ]);
+        let (issue, _) = self
+            .rest
+            .request(Method::PUT, url, &[], Some(&body))
+            .await?;
+        Ok(issue["updated_at"].as_str().map(String::from))
+    }
+}
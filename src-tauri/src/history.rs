@@ -0,0 +1,240 @@
+//! Undo/redo history of the board mutations minik performed
+//!
+//! Every mutation is stored with enough information to invert it, so undoing
+//! is simply applying the inverse mutation.
+
+use crate::backend::BoardBackend;
+use anyhow::Result;
+use log::{debug, error, info};
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Maximum number of mutations kept for undo
+const MAX_HISTORY: usize = 100;
+
+/// A board mutation minik performed
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mutation {
+    /// Item moved between status columns
+    ColumnMove {
+        project_id: String,
+        item_id: String,
+        field_id: String,
+        from_column_id: String,
+        to_column_id: String,
+    },
+    /// Single-select field changed, `None` meaning the field was empty
+    FieldEdit {
+        project_id: String,
+        item_id: String,
+        field_id: String,
+        from_option_id: Option<String>,
+        to_option_id: Option<String>,
+    },
+    /// User assigned to or unassigned from the item's issue/PR
+    Assignment {
+        project_id: String,
+        item_id: String,
+        content_id: String,
+        login: String,
+        assigned: bool,
+    },
+    /// Item archived or unarchived
+    Archive {
+        project_id: String,
+        item_id: String,
+        archived: bool,
+    },
+}
+
+impl Mutation {
+    /// Get the project this mutation touched
+    pub fn project_id(&self) -> &str {
+        match self {
+            Self::ColumnMove { project_id, .. }
+            | Self::FieldEdit { project_id, .. }
+            | Self::Assignment { project_id, .. }
+            | Self::Archive { project_id, .. } => project_id,
+        }
+    }
+
+    /// Get the mutation that undoes this one
+    pub fn inverse(&self) -> Self {
+        match self.clone() {
+            Self::ColumnMove {
+                project_id,
+                item_id,
+                field_id,
+                from_column_id,
+                to_column_id,
+            } => Self::ColumnMove {
+                project_id,
+                item_id,
+                field_id,
+                from_column_id: to_column_id,
+                to_column_id: from_column_id,
+            },
+            Self::FieldEdit {
+                project_id,
+                item_id,
+                field_id,
+                from_option_id,
+                to_option_id,
+            } => Self::FieldEdit {
+                project_id,
+                item_id,
+                field_id,
+                from_option_id: to_option_id,
+                to_option_id: from_option_id,
+            },
+            Self::Assignment {
+                project_id,
+                item_id,
+                content_id,
+                login,
+                assigned,
+            } => Self::Assignment {
+                project_id,
+                item_id,
+                content_id,
+                login,
+                assigned: !assigned,
+            },
+            Self::Archive {
+                project_id,
+                item_id,
+                archived,
+            } => Self::Archive {
+                project_id,
+                item_id,
+                archived: !archived,
+            },
+        }
+    }
+
+    /// Apply this mutation on the board's backend
+    ///
+    /// Column moves and field edits are refused when the field no longer has
+    /// the value this mutation starts from, so undo never clobbers newer changes.
+    pub async fn apply(&self, backend: &dyn BoardBackend) -> Result<()> {
+        match self {
+            Self::ColumnMove {
+                project_id,
+                item_id,
+                field_id,
+                from_column_id,
+                to_column_id,
+            } => {
+                ensure_field_value(backend, item_id, field_id, Some(from_column_id)).await?;
+                backend
+                    .move_item(project_id, item_id, field_id, to_column_id)
+                    .await?;
+            }
+            Self::FieldEdit {
+                project_id,
+                item_id,
+                field_id,
+                from_option_id,
+                to_option_id,
+            } => {
+                ensure_field_value(backend, item_id, field_id, from_option_id.as_deref()).await?;
+                backend
+                    .set_item_field(project_id, item_id, field_id, to_option_id.as_deref())
+                    .await?;
+            }
+            Self::Assignment {
+                content_id,
+                login,
+                assigned,
+                ..
+            } => backend.set_assigned(content_id, login, *assigned).await?,
+            Self::Archive {
+                project_id,
+                item_id,
+                archived,
+            } => {
+                backend
+                    .set_item_archived(project_id, item_id, *archived)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fail unless a field currently holds the expected option
+async fn ensure_field_value(
+    backend: &dyn BoardBackend,
+    item_id: &str,
+    field_id: &str,
+    expected: Option<&str>,
+) -> Result<()> {
+    let current = backend.item_field_state(item_id, field_id).await?;
+    if current.option_id.as_deref() != expected {
+        anyhow::bail!(
+            "Item was changed on {} in the meantime (expected {expected:?}, found {:?})",
+            backend.name(),
+            current.option_id
+        );
+    }
+    Ok(())
+}
+
+/// Undo and redo stacks
+#[derive(Default)]
+struct Stacks {
+    undo: Vec<Mutation>,
+    redo: Vec<Mutation>,
+}
+
+/// Push onto a stack, dropping the oldest entry beyond [`MAX_HISTORY`]
+fn push_capped(stack: &mut Vec<Mutation>, mutation: Mutation) {
+    stack.push(mutation);
+    if stack.len() > MAX_HISTORY {
+        stack.remove(0);
+    }
+}
+
+/// Thread-safe, in-memory mutation history
+#[derive(Default)]
+pub struct History(Mutex<Stacks>);
+
+impl History {
+    /// Record a mutation that was just applied, dropping anything that could be redone
+    pub fn record(&self, mutation: Mutation) {
+        match self.0.lock() {
+            Ok(mut stacks) => {
+                debug!("Recording mutation: {mutation:?}");
+                push_capped(&mut stacks.undo, mutation);
+                stacks.redo.clear();
+            }
+            Err(e) => error!("Failed to lock history: {e}"),
+        }
+    }
+
+    /// Take the most recent mutation to undo
+    pub fn pop_undo(&self) -> Option<Mutation> {
+        self.0.lock().ok()?.undo.pop()
+    }
+
+    /// Take the most recently undone mutation to redo
+    pub fn pop_redo(&self) -> Option<Mutation> {
+        self.0.lock().ok()?.redo.pop()
+    }
+
+    /// Put a mutation back on the undo stack after it was (re)applied, or failed to be undone
+    pub fn push_undo(&self, mutation: Mutation) {
+        if let Ok(mut stacks) = self.0.lock() {
+            push_capped(&mut stacks.undo, mutation);
+        }
+    }
+
+    /// Put a mutation on the redo stack after it was undone, or failed to be redone
+    pub fn push_redo(&self, mutation: Mutation) {
+        if let Ok(mut stacks) = self.0.lock() {
+            info!("Mutation can now be redone: {mutation:?}");
+            push_capped(&mut stacks.redo, mutation);
+        }
+    }
+}
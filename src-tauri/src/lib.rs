@@ -3,50 +3,60 @@
 //! This module provides the core functionality for the Minik Tauri application,
 //! including state management, GitHub API integration, and window management.
 
-mod github;
+pub mod backend;
+mod board;
+mod cache;
+mod catalog;
+mod cli;
+pub mod config;
+mod control;
+mod deeplink;
+mod geometry;
+mod gitea;
+pub mod github;
+mod gitlab;
+mod history;
+mod local;
 mod logging;
-
-use github::{GitHubClient, Organization, Project, ProjectData};
+mod markdown;
+pub mod mcp;
+mod queue;
+mod requests;
+mod rest;
+mod state;
+mod switcher;
+mod traffic;
+
+use backend::{Backends, BoardBackend};
+use board::{BoardStore, BoardUpdate, ItemChange, KnownItem, KnownItems};
+use catalog::{Catalog, CatalogStore};
+use config::{Config, ConfigStore};
+use control::ControlError;
+use deeplink::DeepLink;
+use geometry::{Screen, WindowGeometry};
+use gitea::GiteaClient;
+use github::{GitHubClient, Organization, Project, ProjectData, ProjectItem};
+use gitlab::GitLabClient;
+use history::{History, Mutation};
+use local::LocalBackend;
+use queue::{MutationQueue, PendingMutation};
+use requests::{InFlight, ProjectRequests};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use state::{load_state, save_state, AppStateWrapper, WindowState, MAIN_WINDOW};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use switcher::ProjectMatch;
 use tauri::menu::{Menu, MenuItemBuilder, SubmenuBuilder};
 use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, State, WindowEvent};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tokio_util::sync::CancellationToken;
 
-/// Application state that persists between sessions
-#[derive(Serialize, Deserialize, Clone)]
-struct AppState {
-    selected_project_id: Option<String>,
-    is_expanded: bool,
-    show_only_my_items: bool,
-    hidden_columns: Vec<String>,
-    window_x: i32,
-    window_y: i32,
-    #[serde(default)]
-    last_column_count: u32,
-    #[serde(default)]
-    status_field_id: String,
-    #[serde(default)]
-    project_column_settings: std::collections::HashMap<String, Vec<String>>, // project_id -> hidden columns
-}
-
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            selected_project_id: None,
-            is_expanded: false,
-            show_only_my_items: false,
-            hidden_columns: Vec::new(),
-            window_x: 100,
-            window_y: 50,
-            last_column_count: 5,
-            status_field_id: String::new(),
-            project_column_settings: std::collections::HashMap::new(),
-        }
-    }
-}
+/// Prefix of the labels of additional board windows
+const BOARD_WINDOW_PREFIX: &str = "board-";
 
-/// Thread-safe wrapper for application state
-struct AppStateWrapper(Mutex<AppState>);
+/// Number of recently used projects remembered for the project switcher
+const MAX_RECENT_PROJECTS: usize = 10;
 
 /// Check if GitHub authentication is valid
 #[tauri::command]
@@ -65,20 +75,32 @@ async fn github_token() -> Result<String, String> {
         })
 }
 
-/// List all GitHub organizations the user has access to
+/// List all organizations the user has access to, across every board backend
 #[tauri::command]
-async fn list_organizations() -> Result<Vec<Organization>, String> {
-    log::debug!("Listing GitHub organizations");
-
-    let client = GitHubClient::new().map_err(|e| {
-        log::error!("Failed to create GitHub client: {e}");
-        e.to_string()
-    })?;
-
-    let result = client.list_organizations().await.map_err(|e| {
-        log::error!("Failed to list organizations: {e}");
-        e.to_string()
-    })?;
+async fn list_organizations(backends: State<'_, Backends>) -> Result<Vec<Organization>, String> {
+    log::debug!("Listing organizations");
+
+    // One tracker failing, e.g. without credentials or off the VPN, must not hide the others
+    let mut result = Vec::new();
+    let mut errors = Vec::new();
+    for (prefix, backend) in backends.connect_all() {
+        let listed = match backend {
+            Ok(backend) => backend.list_organizations().await,
+            Err(e) => Err(e),
+        };
+        match listed {
+            Ok(orgs) => result.extend(orgs),
+            Err(e) => {
+                let name = prefix.unwrap_or("GitHub");
+                log::warn!("Skipping {name} organizations: {e}");
+                errors.push(format!("{name}: {e}"));
+            }
+        }
+    }
+    if result.is_empty() && !errors.is_empty() {
+        log::error!("Failed to list organizations: {}", errors.join("; "));
+        return Err(errors.join("; "));
+    }
 
     log::info!("Successfully fetched {} organizations", result.len());
     for org in &result {
@@ -89,18 +111,19 @@ async fn list_organizations() -> Result<Vec<Organization>, String> {
 
 /// List all projects for a specific organization
 #[tauri::command]
-async fn list_org_projects(org: String) -> Result<Vec<Project>, String> {
+async fn list_org_projects(
+    org: String,
+    backends: State<'_, Backends>,
+) -> Result<Vec<Project>, String> {
     log::debug!("Listing projects for organization: {org}");
 
-    let client = GitHubClient::new().map_err(|e| {
-        log::error!("Failed to create GitHub client: {e}");
-        e.to_string()
-    })?;
-
-    let result = client.list_org_projects(&org).await.map_err(|e| {
-        log::error!("Failed to list projects for org {org}: {e}");
-        e.to_string()
-    })?;
+    let result = command_backend(&backends, &org)?
+        .list_projects(&org)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to list projects for org {org}: {e}");
+            e.to_string()
+        })?;
 
     log::info!(
         "Successfully fetched {} projects for org {}",
@@ -110,111 +133,883 @@ async fn list_org_projects(org: String) -> Result<Vec<Project>, String> {
     Ok(result)
 }
 
+/// Fetch the project catalog from the board backends and tell the UI about it
+///
+/// Concurrent refreshes share a single round of requests.
+async fn refresh_catalog<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<Catalog, String> {
+    let previous = app_handle.state::<CatalogStore>().get();
+    let handle = app_handle.clone();
+    let catalog = app_handle
+        .state::<InFlight<Catalog>>()
+        .run("catalog", CancellationToken::new(), async move {
+            Catalog::fetch(&handle.state::<Backends>(), previous.as_ref())
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to fetch project catalog: {e}");
+                    e.to_string()
+                })
+        })
+        .await?;
+
+    app_handle.state::<CatalogStore>().set(catalog.clone());
+    let _ = app_handle.emit("project-catalog-updated", catalog.projects_by_org());
+    Ok(catalog)
+}
+
+/// Get the project catalog, refreshing it in the background once it expired
+async fn current_catalog<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<Catalog, String> {
+    let Some(catalog) = app_handle.state::<CatalogStore>().get() else {
+        log::info!("No project catalog yet, fetching it");
+        return refresh_catalog(app_handle).await;
+    };
+
+    if catalog.is_expired() {
+        log::info!(
+            "Project catalog from {} expired, refreshing in the background",
+            catalog.fetched_at
+        );
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = refresh_catalog(&app_handle).await;
+        });
+    }
+    Ok(catalog)
+}
+
+/// Get the projects of every organization, served from the catalog
+#[tauri::command]
+async fn project_catalog(app_handle: AppHandle) -> Result<BTreeMap<String, Vec<Project>>, String> {
+    Ok(current_catalog(&app_handle).await?.projects_by_org())
+}
+
+/// Fetch the list of organizations and projects from GitHub again
+#[tauri::command]
+async fn refresh_project_list(
+    app_handle: AppHandle,
+) -> Result<BTreeMap<String, Vec<Project>>, String> {
+    log::info!("Refreshing project list");
+    Ok(refresh_catalog(&app_handle).await?.projects_by_org())
+}
+
+/// Backends a background task is already waiting for, by ID prefix (`None` for GitHub)
+static CONNECTIVITY_WATCHES: Mutex<BTreeSet<Option<String>>> = Mutex::new(BTreeSet::new());
+
+/// Poll the backend owning a project until it is reachable again, replay its
+/// queued moves, then tell the frontend which boards to refresh
+fn watch_connectivity(app_handle: AppHandle, project_id: &str) {
+    let prefix = app_handle
+        .state::<Backends>()
+        .prefix_of(project_id)
+        .map(String::from);
+    match CONNECTIVITY_WATCHES.lock() {
+        Ok(mut watches) => {
+            if !watches.insert(prefix.clone()) {
+                log::debug!("Connectivity watcher for {project_id} already running");
+                return;
+            }
+        }
+        Err(e) => {
+            log::error!("Failed to lock connectivity watches: {e}");
+            return;
+        }
+    }
+
+    let project_id = project_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        log::info!("Backend of {project_id} unreachable - waiting for connectivity to return");
+        let name = loop {
+            tokio::time::sleep(Duration::from_secs(15)).await;
+            match app_handle.state::<Backends>().for_id(&project_id) {
+                Ok(backend) if backend.is_reachable().await => break backend.name().to_string(),
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to create board backend for {project_id}: {e}"),
+            }
+        };
+
+        if let Ok(mut watches) = CONNECTIVITY_WATCHES.lock() {
+            watches.remove(&prefix);
+        }
+        log::info!("{name} is reachable again");
+        replay_pending_mutations(&app_handle, prefix.as_deref()).await;
+
+        // Only boards on the backend that came back need a refresh
+        let project_ids: BTreeSet<String> = app_handle
+            .state::<AppStateWrapper>()
+            .0
+            .lock()
+            .map(|app_state| {
+                app_state
+                    .all_windows()
+                    .filter_map(|(_, w)| w.selected_project_id.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let backends = app_handle.state::<Backends>();
+        let restored: Vec<&String> = project_ids
+            .iter()
+            .filter(|id| backends.prefix_of(id) == prefix.as_deref())
+            .collect();
+        let _ = app_handle.emit("connectivity-restored", restored);
+    });
+}
+
+/// Replay the queued column moves of one backend (`None` for GitHub) in order,
+/// stopping if it becomes unreachable again
+///
+/// A move is dropped as a conflict when the item no longer sits in the column
+/// the user moved it from, so a teammate's change is never silently overwritten.
+async fn replay_pending_mutations(app_handle: &AppHandle, prefix: Option<&str>) {
+    let Some(queue) = app_handle.try_state::<MutationQueue>() else {
+        log::error!("Mutation queue not available for replay");
+        return;
+    };
+    let backends = app_handle.state::<Backends>();
+    let owned = |m: &PendingMutation| backends.prefix_of(&m.project_id) == prefix;
+
+    while let Some(mutation) = queue.front_matching(owned) {
+        log::info!(
+            "Replaying queued move of item {} to column {}",
+            mutation.item_id,
+            mutation.to_column_id
+        );
+
+        let backend = match backends.for_id(&mutation.project_id) {
+            Ok(backend) => backend,
+            Err(e) => {
+                log::error!("Failed to create board backend for replay: {e}");
+                return;
+            }
+        };
+        let current = match backend
+            .item_field_state(&mutation.item_id, &mutation.field_id)
+            .await
+        {
+            Ok(current) => current.option_id,
+            Err(e) if github::is_network_error(&e) => {
+                log::warn!("{} unreachable again during replay: {e}", backend.name());
+                watch_connectivity(app_handle.clone(), &mutation.project_id);
+                return;
+            }
+            Err(e) => {
+                log::error!("Dropping queued move of item {}: {e}", mutation.item_id);
+                queue.remove(&mutation, None);
+                let _ = app_handle.emit("mutation-failed", (&mutation, e.to_string()));
+                continue;
+            }
+        };
+
+        // Where the item sits once this mutation is done with
+        let mut landed_in = current.clone();
+        if current.as_deref() == Some(mutation.to_column_id.as_str()) {
+            log::info!("Item {} is already in the target column", mutation.item_id);
+        } else if mutation.from_column_id.is_some() && current != mutation.from_column_id {
+            log::warn!(
+                "Conflict replaying move of item {}: expected column {:?}, found {:?}",
+                mutation.item_id,
+                mutation.from_column_id,
+                current
+            );
+            let _ = app_handle.emit("mutation-conflict", (&mutation, current));
+        } else {
+            match backend
+                .move_item(
+                    &mutation.project_id,
+                    &mutation.item_id,
+                    &mutation.field_id,
+                    &mutation.to_column_id,
+                )
+                .await
+            {
+                Ok(updated_at) => {
+                    log::info!("Replayed queued move of item {}", mutation.item_id);
+                    landed_in = Some(mutation.to_column_id.clone());
+                    record_item_column(
+                        app_handle,
+                        &mutation.project_id,
+                        &mutation.item_id,
+                        &mutation.to_column_id,
+                        updated_at,
+                    );
+                }
+                Err(e) if github::is_network_error(&e) => {
+                    log::warn!("{} unreachable again during replay: {e}", backend.name());
+                    watch_connectivity(app_handle.clone(), &mutation.project_id);
+                    return;
+                }
+                Err(e) => {
+                    log::error!("Dropping queued move of item {}: {e}", mutation.item_id);
+                    let _ = app_handle.emit("mutation-failed", (&mutation, e.to_string()));
+                }
+            }
+        }
+
+        queue.remove(&mutation, landed_in.as_deref());
+    }
+
+    log::info!("Finished replaying queued mutations");
+}
+
+/// Remember that an item now sits in a column, as confirmed by GitHub
+fn record_item_column<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    project_id: &str,
+    item_id: &str,
+    column_id: &str,
+    updated_at: Option<String>,
+) {
+    app_handle.state::<KnownItems>().set(
+        item_id,
+        KnownItem {
+            column_id: column_id.to_string(),
+            updated_at,
+        },
+    );
+    app_handle
+        .state::<BoardStore>()
+        .move_item(project_id, item_id, column_id);
+}
+
+/// Payload of the `item-*` events describing a single change on a board
+#[derive(Serialize, Clone)]
+struct ItemEvent<'a> {
+    project_id: &'a str,
+    #[serde(flatten)]
+    change: &'a ItemChange,
+}
+
+/// Periodically refresh the boards shown in any window in the background
+fn spawn_board_refresher(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Read the interval every time so config changes apply to the next round
+            let interval = app_handle.state::<ConfigStore>().get().refresh_interval();
+            tokio::time::sleep(interval).await;
+
+            let project_ids: BTreeSet<String> = app_handle
+                .state::<AppStateWrapper>()
+                .0
+                .lock()
+                .map(|app_state| {
+                    app_state
+                        .all_windows()
+                        .filter_map(|(_, w)| w.selected_project_id.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            for project_id in project_ids {
+                refresh_board(&app_handle, &project_id).await;
+            }
+        }
+    });
+}
+
+/// Refresh a board in the store and tell the UI exactly what changed
+async fn refresh_board(app_handle: &AppHandle, project_id: &str) {
+    log::debug!("Background refresh of board {project_id}");
+    let store = app_handle.state::<BoardStore>();
+    let previous = store
+        .get(project_id)
+        .filter(|_| !store.needs_full_sync(project_id));
+    let full_sync = previous.is_none();
+
+    let data = match fetch_project_data(app_handle, project_id, previous.as_ref()).await {
+        Ok(data) => data,
+        Err(e) if e == requests::CANCELLED => {
+            log::debug!("Background refresh of board {project_id} was cancelled");
+            return;
+        }
+        Err(e) => {
+            log::warn!("Background refresh of board {project_id} failed: {e}");
+            if e.starts_with(OFFLINE) {
+                watch_connectivity(app_handle.clone(), project_id);
+            }
+            let _ = app_handle.emit("board-stale", project_id);
+            return;
+        }
+    };
+
+    cache::store(&data);
+    app_handle.state::<KnownItems>().record_board(&data);
+    let fetched_at = data.fetched_at;
+
+    match store.update(data, full_sync) {
+        BoardUpdate::Replaced(mut data) => {
+            log::info!("Board {project_id} changed shape, replacing it in the UI");
+            // Every window showing the board has its own hidden columns
+            let windows: Vec<(String, Vec<String>)> = app_handle
+                .state::<AppStateWrapper>()
+                .0
+                .lock()
+                .map(|app_state| {
+                    app_state
+                        .all_windows()
+                        .filter(|(_, w)| w.selected_project_id.as_deref() == Some(project_id))
+                        .map(|(label, w)| (label.to_string(), w.hidden_columns.clone()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            for (label, hidden_columns) in windows {
+                data.hidden_columns = hidden_columns;
+                let _ = app_handle.emit_to(label.as_str(), "board-replaced", &data);
+            }
+        }
+        BoardUpdate::Items(changes) => {
+            for change in &changes {
+                let _ = app_handle.emit(change.event_name(), ItemEvent { project_id, change });
+            }
+        }
+    }
+
+    let _ = app_handle.emit("board-refreshed", (project_id, fetched_at));
+}
+
+/// Start of fetch errors from not reaching the backend, the only ones the cache stands in for
+const OFFLINE: &str = "Offline: ";
+
+/// Fetch fresh project data, joining an identical request if one is already running
+///
+/// The request is cancelled as soon as another project gets selected.
+async fn fetch_project_data<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    project_id: &str,
+    previous: Option<&ProjectData>,
+) -> Result<ProjectData, String> {
+    let key = match previous.and_then(|p| p.fetched_at) {
+        Some(since) => format!("{project_id}@{}", since.to_rfc3339()),
+        None => project_id.to_string(),
+    };
+    let token = app_handle.state::<ProjectRequests>().token(project_id);
+    let backend = command_backend(&app_handle.state(), project_id)?;
+    let project_id = project_id.to_string();
+    let previous = previous.cloned();
+
+    app_handle
+        .state::<InFlight<ProjectData>>()
+        .run(&key, token, async move {
+            request_project_data(backend.as_ref(), &project_id, previous.as_ref()).await
+        })
+        .await
+}
+
+/// Fetch fresh project data from its backend, failing fast when offline
+///
+/// With `previous` data only the items changed since it was fetched are downloaded.
+async fn request_project_data(
+    backend: &dyn BoardBackend,
+    project_id: &str,
+    previous: Option<&ProjectData>,
+) -> Result<ProjectData, String> {
+    if !backend.is_reachable().await {
+        log::warn!(
+            "{} is unreachable, not fetching project {project_id}",
+            backend.name()
+        );
+        return Err(format!("{OFFLINE}{} is unreachable", backend.name()));
+    }
+
+    let result = match previous {
+        Some(previous) => backend.refresh_project_data(previous).await,
+        None => backend.project_data(project_id).await,
+    };
+    result.map_err(|e| {
+        log::error!("Failed to fetch project data for {project_id}: {e}");
+        if github::is_network_error(&e) {
+            format!("{OFFLINE}{e}")
+        } else {
+            e.to_string()
+        }
+    })
+}
+
 /// Fetch detailed data for a specific project
+///
+/// Falls back to the last cached board (marked as stale) when GitHub cannot be reached;
+/// any other error is returned so the window shows it.
 #[tauri::command]
 async fn project_data(
     project_id: String,
     state: State<'_, AppStateWrapper>,
+    known_items: State<'_, KnownItems>,
     app_handle: AppHandle,
+    window: tauri::WebviewWindow,
 ) -> Result<ProjectData, String> {
     log::debug!("Fetching data for project: {project_id}");
-    let client = GitHubClient::new().map_err(|e| {
-        log::error!("Failed to create GitHub client: {e}");
-        e.to_string()
-    })?;
-    let mut result = client.project_data(&project_id).await.map_err(|e| {
-        log::error!("Failed to fetch project data for {project_id}: {e}");
-        e.to_string()
-    })?;
+    let mut result = match fetch_project_data(&app_handle, &project_id, None).await {
+        Ok(data) => {
+            cache::store(&data);
+            known_items.record_board(&data);
+            app_handle.state::<BoardStore>().update(data.clone(), true);
+            data
+        }
+        Err(e) if e == requests::CANCELLED => {
+            log::debug!("Fetching project {project_id} was cancelled");
+            return Err(e);
+        }
+        Err(e) if e.starts_with(OFFLINE) => {
+            let cached = cache::load(&project_id).ok_or(e)?;
+            log::warn!(
+                "Serving cached board for project {project_id} fetched at {:?}",
+                cached.fetched_at
+            );
+            watch_connectivity(app_handle.clone(), &project_id);
+            cached
+        }
+        Err(e) => return Err(e),
+    };
 
     // Add the hidden columns information from the current state
     result.hidden_columns = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?
+        .window(window.label())
         .hidden_columns
         .clone();
 
     log::info!(
-        "Successfully fetched project '{}' with {} columns and {} items",
+        "Successfully fetched project '{}' with {} columns and {} items (stale: {})",
         result.project.title,
         result.columns.len(),
-        result.items.len()
+        result.items.len(),
+        result.stale
     );
 
-    // Store the column count and field ID for later use
-    let mut app_state = state
+    // Store the column count of this window for later use
+    state
         .0
         .lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-    app_state.last_column_count = result.columns.len() as u32;
-    app_state.status_field_id = result.status_field_id.clone();
+        .map_err(|e| format!("Failed to lock state: {}", e))?
+        .window_mut(window.label())
+        .column_count = result.columns.len() as u32;
 
     // Update the hide columns menu dynamically
-    if let Err(e) = update_column_menu(&app_handle, &result.columns, &app_state.hidden_columns) {
+    if let Err(e) = update_column_menu(&app_handle, &result.columns, &result.hidden_columns) {
         log::error!("Failed to update column menu: {e}");
     }
 
     Ok(result)
 }
 
+/// Get the last known board for a project so it can be shown before GitHub answers
+///
+/// Prefers the board held in memory, falling back to the on-disk cache.
+#[tauri::command]
+fn cached_project_data(
+    project_id: String,
+    state: State<AppStateWrapper>,
+    boards: State<BoardStore>,
+    window: tauri::WebviewWindow,
+) -> Option<ProjectData> {
+    let mut data = boards
+        .get(&project_id)
+        .or_else(|| cache::load(&project_id))?;
+    data.hidden_columns = state
+        .0
+        .lock()
+        .ok()?
+        .window(window.label())
+        .hidden_columns
+        .clone();
+    Some(data)
+}
+
+/// Result of asking to move an item to another column
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum MoveOutcome {
+    /// GitHub accepted the move
+    Applied,
+    /// GitHub was unreachable, the move will be replayed once it is back
+    Queued,
+    /// Someone else moved the item since we last saw it, nothing was changed
+    Conflict {
+        /// Column the item is in on GitHub now
+        current_column_id: Option<String>,
+    },
+}
+
 /// Update an item's column (move it to a different status)
+///
+/// The UI moves the card optimistically before calling this. The move is
+/// refused with a conflict when GitHub no longer has the item in the column we
+/// last saw it in, and moves made while GitHub is unreachable are queued on
+/// disk and replayed later.
 #[tauri::command]
 async fn update_item_column(
     project_id: String,
     item_id: String,
     column_id: String,
-    state: State<'_, AppStateWrapper>,
-) -> Result<(), String> {
+    from_column_id: Option<String>,
+    known_items: State<'_, KnownItems>,
+    app_handle: AppHandle,
+) -> Result<MoveOutcome, String> {
     log::info!("\n🎯🎯🎯 UPDATE_ITEM_COLUMN COMMAND CALLED 🎯🎯🎯");
     log::info!("  Project ID: {project_id}");
     log::info!("  Item ID: {item_id}");
+    log::info!("  Source Column ID: {from_column_id:?}");
     log::info!("  Target Column ID: {column_id}");
 
-    let field_id = state
-        .0
-        .lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?
-        .status_field_id
-        .clone();
-    log::info!("  Retrieved Status Field ID from state: '{field_id}'");
+    // The field belongs to the board being edited, not whichever board loaded last
+    let field_id = app_handle
+        .state::<BoardStore>()
+        .status_field_id(&project_id)
+        .or_else(|| cache::load(&project_id).map(|data| data.status_field_id))
+        .unwrap_or_default();
+    log::info!("  Retrieved Status Field ID of the board: '{field_id}'");
 
     if field_id.is_empty() {
         log::error!("❌ Status field ID is empty! Cannot proceed with update.");
         return Err("Status field ID not found - please refresh the project".to_string());
     }
 
-    log::info!("📞 Creating GitHub client...");
-    let client = GitHubClient::new().map_err(|e| {
-        log::error!("❌ Failed to create GitHub client: {e}");
+    // Prefer what the backend last saw over what the UI claims
+    let known = known_items.get(&item_id);
+    let expected_column_id = known
+        .as_ref()
+        .map(|k| k.column_id.clone())
+        .or(from_column_id);
+
+    let enqueue = || {
+        app_handle.state::<MutationQueue>().enqueue(
+            &project_id,
+            &item_id,
+            &field_id,
+            expected_column_id.clone(),
+            &column_id,
+        )?;
+        watch_connectivity(app_handle.clone(), &project_id);
+        Ok(MoveOutcome::Queued)
+    };
+
+    log::info!("📞 Creating board backend...");
+    let backend = command_backend(&app_handle.state(), &project_id)?;
+    log::info!("✅ {} backend created successfully", backend.name());
+
+    if !backend.is_reachable().await {
+        log::warn!("📴 {} unreachable, queueing move for later", backend.name());
+        return enqueue();
+    }
+
+    log::info!("🔍 Checking current item state on {}...", backend.name());
+    let current = match backend.item_field_state(&item_id, &field_id).await {
+        Ok(current) => current,
+        Err(e) if github::is_network_error(&e) => {
+            log::warn!("📴 Lost connection to GitHub, queueing move for later: {e}");
+            return enqueue();
+        }
+        Err(e) => {
+            log::error!("❌ Failed to check item state: {e}");
+            return Err(format!("GitHub API error: {e}"));
+        }
+    };
+
+    if let Some(expected) = &expected_column_id {
+        if current.option_id.as_deref() != Some(expected.as_str()) {
+            log::warn!(
+                "⚠️ Item {item_id} was moved elsewhere: expected column {expected}, found {:?} (version {:?} -> {:?})",
+                current.option_id,
+                known.as_ref().and_then(|k| k.updated_at.as_deref()),
+                current.updated_at
+            );
+            if let Some(option_id) = &current.option_id {
+                record_item_column(
+                    &app_handle,
+                    &project_id,
+                    &item_id,
+                    option_id,
+                    current.updated_at.clone(),
+                );
+            }
+            return Ok(MoveOutcome::Conflict {
+                current_column_id: current.option_id,
+            });
+        }
+    }
+
+    log::info!("🚀 Calling move_item on {} backend...", backend.name());
+    match backend
+        .move_item(&project_id, &item_id, &field_id, &column_id)
+        .await
+    {
+        Ok(updated_at) => {
+            log::info!(
+                "✅✅✅ Successfully updated item column on {}!",
+                backend.name()
+            );
+            record_item_column(&app_handle, &project_id, &item_id, &column_id, updated_at);
+            if let Some(from_column_id) = expected_column_id.clone() {
+                app_handle.state::<History>().record(Mutation::ColumnMove {
+                    project_id: project_id.clone(),
+                    item_id: item_id.clone(),
+                    field_id: field_id.clone(),
+                    from_column_id,
+                    to_column_id: column_id.clone(),
+                });
+            }
+            Ok(MoveOutcome::Applied)
+        }
+        Err(e) if github::is_network_error(&e) => {
+            log::warn!("📴 Lost connection to GitHub, queueing move for later: {e}");
+            enqueue()
+        }
+        Err(e) => {
+            log::error!("❌❌❌ Failed to update item column: {e}");
+            Err(format!("GitHub API error: {e}"))
+        }
+    }
+}
+
+/// Get the moves for a project that are still waiting to reach GitHub
+#[tauri::command]
+fn pending_mutations(project_id: String, queue: State<MutationQueue>) -> Vec<PendingMutation> {
+    queue.for_project(&project_id)
+}
+
+/// Get the backend owning an organization or project for a command, logging failures
+fn command_backend(backends: &Backends, id: &str) -> Result<Arc<dyn BoardBackend>, String> {
+    backends.for_id(id).map_err(|e| {
+        log::error!("Failed to create board backend for {id}: {e}");
         e.to_string()
-    })?;
-    log::info!("✅ GitHub client created successfully");
+    })
+}
+
+/// Apply a mutation on its board's backend and keep the known item state in sync
+async fn apply_mutation<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    mutation: &Mutation,
+) -> anyhow::Result<()> {
+    let backend = app_handle
+        .state::<Backends>()
+        .for_id(mutation.project_id())?;
+    if let Err(e) = mutation.apply(backend.as_ref()).await {
+        log::error!("Failed to apply mutation {mutation:?}: {e}");
+        return Err(e);
+    }
+
+    if let Mutation::ColumnMove {
+        project_id,
+        item_id,
+        to_column_id,
+        ..
+    } = mutation
+    {
+        record_item_column(app_handle, project_id, item_id, to_column_id, None);
+    }
+    Ok(())
+}
 
-    log::info!("🚀 Calling update_item_field on GitHub client...");
-    client
-        .update_item_field(&project_id, &item_id, &field_id, &column_id)
+/// Apply a mutation requested by the UI and record it for undo
+async fn perform_mutation(app_handle: &AppHandle, mutation: Mutation) -> Result<(), String> {
+    apply_mutation(app_handle, &mutation)
+        .await
+        .map_err(|e| e.to_string())?;
+    app_handle.state::<History>().record(mutation);
+    Ok(())
+}
+
+/// Set a single-select field on an item, or clear it when no option is given
+#[tauri::command]
+async fn update_item_field_value(
+    project_id: String,
+    item_id: String,
+    field_id: String,
+    option_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting field {field_id} of item {item_id} to {option_id:?}");
+    let current = command_backend(&app_handle.state(), &project_id)?
+        .item_field_state(&item_id, &field_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    perform_mutation(
+        &app_handle,
+        Mutation::FieldEdit {
+            project_id,
+            item_id,
+            field_id,
+            from_option_id: current.option_id,
+            to_option_id: option_id,
+        },
+    )
+    .await
+}
+
+/// Assign a user to an item's issue/PR, or remove the assignment
+#[tauri::command]
+async fn assign_item(
+    project_id: String,
+    item_id: String,
+    login: String,
+    assigned: bool,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting assigned={assigned} for {login} on item {item_id}");
+    let content_id = command_backend(&app_handle.state(), &project_id)?
+        .item_content_id(&item_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    perform_mutation(
+        &app_handle,
+        Mutation::Assignment {
+            project_id,
+            item_id,
+            content_id,
+            login,
+            assigned,
+        },
+    )
+    .await
+}
+
+/// Archive or unarchive an item
+#[tauri::command]
+async fn archive_item(
+    project_id: String,
+    item_id: String,
+    archived: bool,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting archived={archived} on item {item_id}");
+    perform_mutation(
+        &app_handle,
+        Mutation::Archive {
+            project_id,
+            item_id,
+            archived,
+        },
+    )
+    .await
+}
+
+/// Add a card to a column of a board
+#[tauri::command]
+async fn create_item(
+    project_id: String,
+    column_id: String,
+    title: String,
+    app_handle: AppHandle,
+) -> Result<ProjectItem, String> {
+    log::info!("Adding item to column {column_id} of project {project_id}");
+    let item = command_backend(&app_handle.state(), &project_id)?
+        .create_item(&project_id, &column_id, &title)
         .await
-        .map(|()| {
-            log::info!("✅✅✅ Successfully updated item column on GitHub!");
-        })
         .map_err(|e| {
-            log::error!("❌❌❌ Failed to update item column: {e}");
-            format!("GitHub API error: {e}")
-        })
+            log::error!("Failed to add item to project {project_id}: {e}");
+            e.to_string()
+        })?;
+
+    // The refresh tells every window showing the board about the new card
+    refresh_board(&app_handle, &project_id).await;
+    Ok(item)
+}
+
+/// Delete a card from a board
+#[tauri::command]
+async fn delete_item(
+    project_id: String,
+    item_id: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Deleting item {item_id} from project {project_id}");
+    command_backend(&app_handle.state(), &project_id)?
+        .delete_item(&project_id, &item_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to delete item {item_id}: {e}");
+            e.to_string()
+        })?;
+
+    refresh_board(&app_handle, &project_id).await;
+    Ok(())
+}
+
+/// Undo the most recent mutation, returning the mutation that was applied to do so
+async fn undo_last<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Option<Mutation>, String> {
+    let history = app_handle.state::<History>();
+    let Some(mutation) = history.pop_undo() else {
+        log::info!("Nothing to undo");
+        return Ok(None);
+    };
+
+    let inverse = mutation.inverse();
+    log::info!(
+        "Undoing mutation on project {}: {mutation:?}",
+        mutation.project_id()
+    );
+    if let Err(e) = apply_mutation(app_handle, &inverse).await {
+        // Keep it undoable for when the backend is back, but a conflict will never resolve
+        if github::is_network_error(&e) {
+            history.push_undo(mutation);
+        } else {
+            log::warn!("Dropping mutation that can no longer be undone: {mutation:?}");
+        }
+        return Err(e.to_string());
+    }
+    history.push_redo(mutation);
+
+    let _ = app_handle.emit("history-applied", &inverse);
+    Ok(Some(inverse))
+}
+
+/// Redo the most recently undone mutation
+async fn redo_last<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Option<Mutation>, String> {
+    let history = app_handle.state::<History>();
+    let Some(mutation) = history.pop_redo() else {
+        log::info!("Nothing to redo");
+        return Ok(None);
+    };
+
+    log::info!(
+        "Redoing mutation on project {}: {mutation:?}",
+        mutation.project_id()
+    );
+    if let Err(e) = apply_mutation(app_handle, &mutation).await {
+        if github::is_network_error(&e) {
+            history.push_redo(mutation);
+        } else {
+            log::warn!("Dropping mutation that can no longer be redone: {mutation:?}");
+        }
+        return Err(e.to_string());
+    }
+    history.push_undo(mutation.clone());
+
+    let _ = app_handle.emit("history-applied", &mutation);
+    Ok(Some(mutation))
+}
+
+/// Undo the most recent board mutation
+#[tauri::command]
+async fn undo(app_handle: AppHandle) -> Result<Option<Mutation>, String> {
+    undo_last(&app_handle).await
+}
+
+/// Redo the most recently undone board mutation
+#[tauri::command]
+async fn redo(app_handle: AppHandle) -> Result<Option<Mutation>, String> {
+    redo_last(&app_handle).await
 }
 
 /// Toggle the expanded state of the window
 #[tauri::command]
-fn toggle_expanded(state: State<AppStateWrapper>, _app_handle: AppHandle) -> Result<bool, String> {
-    log::debug!("Toggling window expanded state");
+fn toggle_expanded(
+    state: State<AppStateWrapper>,
+    window: tauri::WebviewWindow,
+) -> Result<bool, String> {
+    log::debug!("Toggling expanded state of window {}", window.label());
     let mut app_state = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
-    app_state.is_expanded = !app_state.is_expanded;
-    let is_expanded = app_state.is_expanded;
-    let column_count = app_state.last_column_count;
+    let window_state = app_state.window_mut(window.label());
+    window_state.is_expanded = !window_state.is_expanded;
+    let is_expanded = window_state.is_expanded;
+    let column_count = window_state.column_count;
     log::info!("Window expanded state changed to: {is_expanded}, columns: {column_count}");
 
     // Note: Window sizing is now handled dynamically by JavaScript
@@ -226,19 +1021,17 @@ fn toggle_expanded(state: State<AppStateWrapper>, _app_handle: AppHandle) -> Res
 
 /// Resize the window to fit a specific number of columns
 #[tauri::command]
-fn resize_window_for_columns(column_count: u32, app_handle: AppHandle) -> Result<(), String> {
+fn resize_window_for_columns(
+    column_count: u32,
+    window: tauri::WebviewWindow,
+    config: State<ConfigStore>,
+) -> Result<(), String> {
     log::debug!("Resizing window for {column_count} columns");
 
-    if let Some(window) = app_handle.get_webview_window("main") {
+    {
         // Calculate width: base padding + (column width * count) + gaps
         // Using Logical size to handle HiDPI displays correctly
-        let column_width = 190; // Width per column
-        let padding = 12; // Total padding
-        let gap = 4; // Gap between columns
-
-        // Calculate exact width needed for visible columns
-        let width =
-            padding + (column_width * column_count) + (gap * column_count.saturating_sub(1));
+        let width = config.get().layout.board_width(column_count);
         // No artificial max width limit
 
         let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize {
@@ -256,19 +1049,14 @@ fn resize_window_for_columns(column_count: u32, app_handle: AppHandle) -> Result
 fn resize_window_with_height(
     column_count: u32,
     height: u32,
-    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
+    config: State<ConfigStore>,
 ) -> Result<(), String> {
     log::debug!("Resizing window for {column_count} columns with height {height}");
 
-    if let Some(window) = app_handle.get_webview_window("main") {
+    {
         // Calculate width: base padding + (column width * count) + gaps
-        let column_width = 190; // Width per column
-        let padding = 12; // Total padding
-        let gap = 4; // Gap between columns
-
-        // Calculate exact width needed for visible columns
-        let width =
-            padding + (column_width * column_count) + (gap * column_count.saturating_sub(1));
+        let width = config.get().layout.board_width(column_count);
 
         // Use the exact height from JavaScript without any artificial caps
         let final_height = height;
@@ -288,11 +1076,11 @@ fn resize_window_with_height(
 fn resize_window_to_dimensions(
     width: u32,
     height: u32,
-    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
 ) -> Result<(), String> {
     log::debug!("Resizing window to exact dimensions: {width}x{height}");
 
-    if let Some(window) = app_handle.get_webview_window("main") {
+    {
         let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize {
             width: f64::from(width),
             height: f64::from(height),
@@ -308,18 +1096,14 @@ fn resize_window_to_dimensions(
 fn resize_for_context_menu(
     column_count: u32,
     show_menu: bool,
-    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
+    config: State<ConfigStore>,
 ) -> Result<(), String> {
     log::debug!("Resizing for context menu: show={show_menu}, columns={column_count}");
 
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let column_width = 190;
-        let padding = 12;
-        let gap = 4;
-
+    {
         // Calculate base width needed for visible columns
-        let content_width =
-            padding + (column_width * column_count) + (gap * column_count.saturating_sub(1));
+        let content_width = config.get().layout.board_width(column_count);
 
         // Add extra space for context menu only when shown
         let width = if show_menu {
@@ -343,60 +1127,205 @@ fn resize_for_context_menu(
 #[tauri::command]
 fn select_project(
     project_id: String,
-    state: State<AppStateWrapper>,
     app_handle: AppHandle,
+    window: tauri::WebviewWindow,
 ) -> Result<(), String> {
-    log::info!("Selecting project: {project_id}");
+    select_project_in_window(&app_handle, &window, project_id)
+}
+
+/// Show a project in a window, remembering the choice
+fn select_project_in_window(
+    app_handle: &AppHandle,
+    window: &tauri::WebviewWindow,
+    project_id: String,
+) -> Result<(), String> {
+    log::info!(
+        "Selecting project {project_id} in window {}",
+        window.label()
+    );
+    let state = app_handle.state::<AppStateWrapper>();
     let mut app_state = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
-    let old_project = app_state.selected_project_id.clone();
+    let old_project = app_state.window(window.label()).selected_project_id.clone();
+
+    // Nobody is waiting for the previous project's data any more, unless another window shows it
+    if let Some(old_id) = old_project.as_ref().filter(|id| **id != project_id) {
+        let shown_elsewhere = app_state.all_windows().any(|(label, w)| {
+            label != window.label() && w.selected_project_id.as_ref() == Some(old_id)
+        });
+        if !shown_elsewhere {
+            app_handle.state::<ProjectRequests>().cancel(old_id);
+        }
+    }
 
     // Save current project's hidden columns
     if let Some(old_id) = old_project {
-        let hidden_cols = app_state.hidden_columns.clone();
+        let hidden_cols = app_state.window(window.label()).hidden_columns.clone();
         app_state
             .project_column_settings
             .insert(old_id, hidden_cols);
     }
 
+    // Move the project to the front of the recently used list
+    app_state.recent_projects.retain(|id| *id != project_id);
+    app_state.recent_projects.insert(0, project_id.clone());
+    app_state.recent_projects.truncate(MAX_RECENT_PROJECTS);
+
     // Load new project's hidden columns
-    app_state.selected_project_id = Some(project_id.clone());
-    app_state.hidden_columns = app_state
+    let hidden_columns = app_state
         .project_column_settings
         .get(&project_id)
         .cloned()
         .unwrap_or_default();
+    let window_state = app_state.window_mut(window.label());
+    window_state.selected_project_id = Some(project_id.clone());
+    window_state.hidden_columns = hidden_columns;
 
     save_state(&app_state);
+    drop(app_state);
+
+    // Go to where this project's board was last shown
+    restore_window_position(window);
 
     // Emit event to reload project data
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.emit("project-changed", project_id.clone());
-    }
+    let _ = window.emit_to(window.label(), "project-changed", project_id.clone());
 
     log::debug!("Project {project_id} selected and state saved");
     Ok(())
 }
 
+/// Find the ID of a project given as `org/number` or as a project ID
+///
+/// The catalog is fetched again once if the project is not in it, since it
+/// may have been created after the catalog was.
+async fn resolve_project(app_handle: &AppHandle, project: &str) -> Option<String> {
+    let catalog = current_catalog(app_handle).await.ok()?;
+    if let Some(project_id) = catalog.resolve(project) {
+        return Some(project_id);
+    }
+
+    log::info!("Project {project} not in the catalog, refreshing it");
+    let project_id = refresh_catalog(app_handle).await.ok()?.resolve(project);
+    if project_id.is_none() {
+        log::warn!("Project {project} not found");
+    }
+    project_id
+}
+
+/// Find projects for the quick switcher, favorites and recently used ones first
+#[tauri::command]
+async fn search_projects(
+    query: String,
+    state: State<'_, AppStateWrapper>,
+    app_handle: AppHandle,
+) -> Result<Vec<ProjectMatch>, String> {
+    let catalog = current_catalog(&app_handle).await?;
+    let (recent, favorites) = {
+        let app_state = state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to lock state: {}", e))?;
+        (
+            app_state.recent_projects.clone(),
+            app_state.favorite_projects.clone(),
+        )
+    };
+
+    let matches = switcher::rank_projects(&catalog, &query, &recent, &favorites);
+    log::debug!(
+        "Project search '{query}' matched {} projects",
+        matches.len()
+    );
+    Ok(matches)
+}
+
+/// Star or unstar a project, returning whether it is now a favorite
+#[tauri::command]
+fn toggle_favorite_project(
+    project_id: String,
+    state: State<AppStateWrapper>,
+) -> Result<bool, String> {
+    let mut app_state = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+
+    let favorite = if app_state.favorite_projects.contains(&project_id) {
+        app_state.favorite_projects.retain(|id| *id != project_id);
+        false
+    } else {
+        app_state.favorite_projects.push(project_id.clone());
+        true
+    };
+
+    save_state(&app_state);
+    log::info!("Project {project_id} favorite: {favorite}");
+    Ok(favorite)
+}
+
 /// Get the currently selected project ID
+///
+/// A project given on the command line replaces the main window's saved one,
+/// and windows without a project fall back to the default project from the config.
 #[tauri::command]
-fn current_project(state: State<AppStateWrapper>) -> Option<String> {
-    let app_state = state.0.lock().ok()?;
-    app_state.selected_project_id.clone()
+async fn current_project(app_handle: AppHandle, window: tauri::WebviewWindow) -> Option<String> {
+    let launch_project = if window.label() == MAIN_WINDOW {
+        app_handle
+            .state::<LaunchProject>()
+            .0
+            .lock()
+            .ok()
+            .and_then(|mut project| project.take())
+    } else {
+        None
+    };
+    let selected = {
+        let state = app_handle.state::<AppStateWrapper>();
+        let app_state = state.0.lock().ok()?;
+        app_state.window(window.label()).selected_project_id.clone()
+    };
+
+    let requested = match launch_project {
+        Some(project) => project,
+        None if selected.is_some() => return selected,
+        None => app_handle.state::<ConfigStore>().get().default_project?,
+    };
+    let Some(project_id) = resolve_project(&app_handle, &requested).await else {
+        return selected;
+    };
+    log::info!("Opening requested project {requested} ({project_id})");
+
+    if let Err(e) = select_project_in_window(&app_handle, &window, project_id.clone()) {
+        log::error!("Failed to select project {project_id}: {e}");
+        return selected;
+    }
+    Some(project_id)
+}
+
+/// Get the settings from the config file
+#[tauri::command]
+fn app_config(config: State<ConfigStore>) -> Config {
+    config.get()
 }
 
 /// Toggle the "show only my items" filter
 #[tauri::command]
-fn toggle_my_items(state: State<AppStateWrapper>) -> Result<bool, String> {
+fn toggle_my_items(
+    state: State<AppStateWrapper>,
+    window: tauri::WebviewWindow,
+) -> Result<bool, String> {
     let mut app_state = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
-    app_state.show_only_my_items = !app_state.show_only_my_items;
+    let window_state = app_state.window_mut(window.label());
+    window_state.show_only_my_items = !window_state.show_only_my_items;
+    let show_only_my_items = window_state.show_only_my_items;
     save_state(&app_state);
-    Ok(app_state.show_only_my_items)
+    let _ = window.emit_to(window.label(), "my-items-changed", show_only_my_items);
+    Ok(show_only_my_items)
 }
 
 /// Toggle the visibility of a specific column
@@ -404,26 +1333,28 @@ fn toggle_my_items(state: State<AppStateWrapper>) -> Result<bool, String> {
 fn toggle_column_visibility(
     column_id: String,
     state: State<AppStateWrapper>,
+    window: tauri::WebviewWindow,
 ) -> Result<bool, String> {
     let mut app_state = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
-    let is_visible = if let Some(index) = app_state
+    let window_state = app_state.window_mut(window.label());
+    let is_visible = if let Some(index) = window_state
         .hidden_columns
         .iter()
         .position(|c| c == &column_id)
     {
-        app_state.hidden_columns.remove(index);
+        window_state.hidden_columns.remove(index);
         true
     } else {
-        app_state.hidden_columns.push(column_id.clone());
+        window_state.hidden_columns.push(column_id.clone());
         false
     };
 
     // Also update the project-specific settings
-    let hidden_cols = app_state.hidden_columns.clone();
-    if let Some(project_id) = app_state.selected_project_id.clone() {
+    let hidden_cols = window_state.hidden_columns.clone();
+    if let Some(project_id) = window_state.selected_project_id.clone() {
         app_state
             .project_column_settings
             .insert(project_id, hidden_cols);
@@ -439,20 +1370,22 @@ fn hide_column(
     project_id: String,
     column_id: String,
     state: State<AppStateWrapper>,
+    window: tauri::WebviewWindow,
 ) -> Result<(), String> {
     log::info!("Hiding column {column_id} for project {project_id}");
     let mut app_state = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let window_state = app_state.window_mut(window.label());
 
     // Add to hidden columns if not already hidden
-    if !app_state.hidden_columns.contains(&column_id) {
-        app_state.hidden_columns.push(column_id.clone());
+    if !window_state.hidden_columns.contains(&column_id) {
+        window_state.hidden_columns.push(column_id.clone());
     }
 
     // Update project-specific settings
-    let hidden_cols = app_state.hidden_columns.clone();
+    let hidden_cols = window_state.hidden_columns.clone();
     app_state
         .project_column_settings
         .insert(project_id, hidden_cols);
@@ -468,24 +1401,26 @@ fn show_column(
     project_id: String,
     column_id: String,
     state: State<AppStateWrapper>,
+    window: tauri::WebviewWindow,
 ) -> Result<(), String> {
     log::info!("Showing column {column_id} for project {project_id}");
     let mut app_state = state
         .0
         .lock()
         .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let window_state = app_state.window_mut(window.label());
 
     // Remove from hidden columns
-    if let Some(index) = app_state
+    if let Some(index) = window_state
         .hidden_columns
         .iter()
         .position(|c| c == &column_id)
     {
-        app_state.hidden_columns.remove(index);
+        window_state.hidden_columns.remove(index);
     }
 
     // Update project-specific settings
-    let hidden_cols = app_state.hidden_columns.clone();
+    let hidden_cols = window_state.hidden_columns.clone();
     app_state
         .project_column_settings
         .insert(project_id, hidden_cols);
@@ -497,9 +1432,9 @@ fn show_column(
 
 /// Get the list of hidden column IDs
 #[tauri::command]
-fn hidden_columns(state: State<AppStateWrapper>) -> Vec<String> {
+fn hidden_columns(state: State<AppStateWrapper>, window: tauri::WebviewWindow) -> Vec<String> {
     match state.0.lock() {
-        Ok(app_state) => app_state.hidden_columns.clone(),
+        Ok(app_state) => app_state.window(window.label()).hidden_columns.clone(),
         Err(e) => {
             log::error!("Failed to lock state for hidden_columns: {}", e);
             Vec::new()
@@ -509,9 +1444,9 @@ fn hidden_columns(state: State<AppStateWrapper>) -> Vec<String> {
 
 /// Check if the window is in expanded state
 #[tauri::command]
-fn is_expanded(state: State<AppStateWrapper>) -> bool {
+fn is_expanded(state: State<AppStateWrapper>, window: tauri::WebviewWindow) -> bool {
     match state.0.lock() {
-        Ok(app_state) => app_state.is_expanded,
+        Ok(app_state) => app_state.window(window.label()).is_expanded,
         Err(e) => {
             log::error!("Failed to lock state for is_expanded: {}", e);
             false
@@ -521,9 +1456,9 @@ fn is_expanded(state: State<AppStateWrapper>) -> bool {
 
 /// Check if the "show only my items" filter is active
 #[tauri::command]
-fn show_only_my_items(state: State<AppStateWrapper>) -> bool {
+fn show_only_my_items(state: State<AppStateWrapper>, window: tauri::WebviewWindow) -> bool {
     match state.0.lock() {
-        Ok(app_state) => app_state.show_only_my_items,
+        Ok(app_state) => app_state.window(window.label()).show_only_my_items,
         Err(e) => {
             log::error!("Failed to lock state for show_only_my_items: {}", e);
             false
@@ -561,6 +1496,10 @@ fn find_gh_command() -> Result<String, String> {
 /// Get the current GitHub username
 #[tauri::command]
 async fn current_user() -> Result<String, String> {
+    if let Some(account) = github::account() {
+        return Ok(account.to_string());
+    }
+
     log::info!("Fetching current user from GitHub");
     let gh_path = find_gh_command()?;
     let output = std::process::Command::new(&gh_path)
@@ -569,68 +1508,14 @@ async fn current_user() -> Result<String, String> {
         .map_err(|e| format!("Failed to execute gh command: {e}"))?;
 
     if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        log::error!("Failed to get current user: {error}");
-        return Err(format!("Failed to get current user: {error}"));
-    }
-
-    let username = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    log::info!("Current GitHub user: {username}");
-    Ok(username)
-}
-
-fn save_state(state: &AppState) {
-    log::debug!("Saving application state");
-    match serde_json::to_string(state) {
-        Ok(json) => {
-            let path = dirs::config_dir().map(|p| p.join("minik").join("state.json"));
-
-            if let Some(path) = path {
-                if let Some(parent) = path.parent() {
-                    if let Err(e) = std::fs::create_dir_all(parent) {
-                        log::error!("Failed to create config directory: {e}");
-                        return;
-                    }
-                }
-                match std::fs::write(&path, json) {
-                    Ok(()) => log::debug!("State saved successfully to {path:?}"),
-                    Err(e) => log::error!("Failed to write state to {path:?}: {e}"),
-                }
-            } else {
-                log::error!("Could not determine config directory");
-            }
-        }
-        Err(e) => log::error!("Failed to serialize state: {e}"),
-    }
-}
-
-fn load_state() -> AppState {
-    log::debug!("Loading application state");
-    let path = dirs::config_dir().map(|p| p.join("minik").join("state.json"));
-
-    if let Some(path) = path {
-        match std::fs::read_to_string(&path) {
-            Ok(json) => match serde_json::from_str(&json) {
-                Ok(state) => {
-                    log::info!("State loaded successfully from {path:?}");
-                    return state;
-                }
-                Err(e) => log::warn!("Failed to parse state file: {e}"),
-            },
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    log::debug!("No existing state file found, using defaults");
-                } else {
-                    log::warn!("Failed to read state file: {e}");
-                }
-            }
-        }
-    } else {
-        log::warn!("Could not determine config directory");
+        let error = String::from_utf8_lossy(&output.stderr);
+        log::error!("Failed to get current user: {error}");
+        return Err(format!("Failed to get current user: {error}"));
     }
 
-    log::info!("Using default application state");
-    AppState::default()
+    let username = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    log::info!("Current GitHub user: {username}");
+    Ok(username)
 }
 
 /// Update the project menu dynamically
@@ -654,53 +1539,25 @@ async fn update_columns_menu(
 
 /// Show the project selection context menu
 #[tauri::command]
-async fn show_project_context_menu(app_handle: AppHandle) -> Result<(), String> {
-    use futures::future::join_all;
+async fn show_project_context_menu(
+    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
+) -> Result<(), String> {
     log::debug!("Showing project context menu");
 
-    // Get organizations first
-    let orgs = list_organizations()
-        .await
-        .map_err(|e| format!("Failed to get organizations: {e}"))?;
-
-    // Fetch all projects in parallel
-    let org_project_futures: Vec<_> = orgs
-        .iter()
-        .map(|org| {
-            let org_login = org.login.clone();
-            async move {
-                let projects = list_org_projects(org_login.clone())
-                    .await
-                    .unwrap_or_default();
-                (org_login, projects)
-            }
-        })
-        .collect();
-
-    let org_projects = join_all(org_project_futures).await;
-
-    // Build a simple HashMap-like structure for the frontend
-    let projects_by_org: std::collections::HashMap<String, Vec<serde_json::Value>> = org_projects
-        .into_iter()
-        .filter(|(_, projects)| !projects.is_empty())
-        .map(|(org_login, projects)| {
-            let project_values = projects
-                .into_iter()
-                .filter_map(|p| serde_json::to_value(p).ok())
-                .collect();
-            (org_login, project_values)
-        })
-        .collect();
+    let projects_by_org = current_catalog(&app_handle).await?.projects_by_org();
 
     log::info!(
         "Sending projects to frontend: {:?}",
         projects_by_org.keys().collect::<Vec<_>>()
     );
 
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let result = window.emit("show-project-context-menu-with-projects", &projects_by_org);
-        log::info!("Event emit result: {result:?}");
-    }
+    let result = window.emit_to(
+        window.label(),
+        "show-project-context-menu-with-projects",
+        &projects_by_org,
+    );
+    log::info!("Event emit result: {result:?}");
 
     Ok(())
 }
@@ -711,18 +1568,35 @@ async fn show_column_context_menu(
     project_id: String,
     app_handle: AppHandle,
     state: State<'_, AppStateWrapper>,
+    window: tauri::WebviewWindow,
 ) -> Result<(), String> {
     log::debug!("Showing column context menu for project: {project_id}");
 
-    // Get project data to build the context menu
-    let project_data = project_data(project_id.clone(), state, app_handle.clone()).await?;
+    // Columns rarely change, so prefer the board we already have over asking GitHub
+    let known = app_handle
+        .state::<BoardStore>()
+        .get(&project_id)
+        .or_else(|| cache::load(&project_id));
+    let columns = match known {
+        Some(data) => data.columns,
+        None => {
+            project_data(
+                project_id.clone(),
+                state,
+                app_handle.state::<KnownItems>(),
+                app_handle.clone(),
+                window.clone(),
+            )
+            .await?
+            .columns
+        }
+    };
 
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.emit(
-            "show-column-context-menu",
-            (project_id, project_data.columns),
-        );
-    }
+    let _ = window.emit_to(
+        window.label(),
+        "show-column-context-menu",
+        (project_id, columns),
+    );
 
     Ok(())
 }
@@ -731,8 +1605,8 @@ async fn rebuild_project_menu<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> R
     // For now, we'll emit events to the frontend to handle project selection
     // Dynamic menu updates in Tauri v2 are complex and require rebuilding the entire menu
     log::info!("Project menu update requested - using frontend modal instead");
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.emit("show-project-selector", ());
+    if let Some(window) = menu_target(app_handle) {
+        let _ = window.emit_to(window.label(), "show-project-selector", ());
     }
     Ok(())
 }
@@ -742,12 +1616,283 @@ fn rebuild_columns_menu<R: tauri::Runtime>(
     columns: Vec<github::ProjectColumn>,
 ) -> Result<(), String> {
     // Store columns data for frontend use
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.emit("columns-updated", columns);
+    if let Some(window) = menu_target(app_handle) {
+        let _ = window.emit_to(window.label(), "columns-updated", columns);
+    }
+    Ok(())
+}
+
+/// Get the window menu actions apply to: the focused board window, else the main one
+fn menu_target<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Option<tauri::WebviewWindow<R>> {
+    app_handle
+        .webview_windows()
+        .into_values()
+        .find(|window| window.is_focused().unwrap_or(false))
+        .or_else(|| app_handle.get_webview_window(MAIN_WINDOW))
+}
+
+/// Get the monitors a window can be shown on
+fn screens<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) -> Vec<Screen> {
+    window
+        .available_monitors()
+        .map(|monitors| monitors.iter().map(Screen::from).collect())
+        .unwrap_or_default()
+}
+
+/// Move a window to where it was last shown, keeping it on a connected monitor
+///
+/// Uses the position saved for the window's project on the current display
+/// configuration, falling back to the window's last position. The size is left
+/// alone, as the board sets it for its columns and expanded state.
+fn restore_window_position<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) {
+    let Some(state_wrapper) = window.try_state::<AppStateWrapper>() else {
+        return;
+    };
+    let screens = screens(window);
+    let display = geometry::display_key(&screens);
+
+    let (saved, project_geometry) = {
+        let Ok(app_state) = state_wrapper.0.lock() else {
+            log::error!("Failed to lock state for window position restoration");
+            return;
+        };
+        let window_state = app_state.window(window.label());
+        let project_geometry = window_state
+            .selected_project_id
+            .as_ref()
+            .and_then(|id| app_state.project_geometry.get(id))
+            .and_then(|displays| displays.get(&display))
+            .copied();
+        (
+            (window_state.window_x, window_state.window_y),
+            project_geometry,
+        )
+    };
+
+    let size = window.outer_size().unwrap_or_default();
+    let (x, y) = project_geometry.map_or(saved, |g| (g.x, g.y));
+    let target = WindowGeometry {
+        x,
+        y,
+        width: size.width,
+        height: size.height,
+    };
+    let fitted = geometry::fit_on_screen(target, &screens);
+
+    let _ = window.set_position(PhysicalPosition::new(fitted.x, fitted.y));
+    log::info!(
+        "Restored position of window {} to ({}, {}) on display configuration {display}",
+        window.label(),
+        fitted.x,
+        fitted.y
+    );
+}
+
+/// Remember where a window is, for itself and for the project it shows
+fn remember_window_geometry<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) {
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+        return;
+    };
+    let display = geometry::display_key(&screens(window));
+    let Some(state_wrapper) = window.try_state::<AppStateWrapper>() else {
+        return;
+    };
+    let Ok(mut app_state) = state_wrapper.0.lock() else {
+        log::error!("Failed to lock state for window position save");
+        return;
+    };
+
+    // Closed windows may still report a last move
+    let label = window.label();
+    if label != MAIN_WINDOW && !app_state.windows.contains_key(label) {
+        return;
+    }
+
+    let window_state = app_state.window_mut(label);
+    window_state.window_x = position.x;
+    window_state.window_y = position.y;
+
+    if let Some(project_id) = window_state.selected_project_id.clone() {
+        app_state
+            .project_geometry
+            .entry(project_id)
+            .or_default()
+            .insert(
+                display,
+                WindowGeometry {
+                    x: position.x,
+                    y: position.y,
+                    width: size.width,
+                    height: size.height,
+                },
+            );
     }
+    save_state(&app_state);
+}
+
+/// Save a window's position when it moves, and forget extra windows once closed
+///
+/// Resizes are not saved: windows can't be resized by hand, so every resize is
+/// the app fitting the board or expanding and collapsing it.
+fn watch_window_events<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) {
+    let app_handle = window.app_handle().clone();
+    let label = window.label().to_string();
+
+    window.on_window_event(move |event| match event {
+        WindowEvent::Focused(false) => {
+            log::debug!("Window {label} lost focus");
+        }
+        WindowEvent::Focused(true) => {
+            log::debug!("Window {label} gained focus");
+        }
+        WindowEvent::Resized(size) => {
+            log::debug!("Window {label} resized to: {size:?}");
+        }
+        WindowEvent::Moved(position) => {
+            log::debug!("Window {label} moved to: {position:?}");
+            if let Some(window) = app_handle.get_webview_window(&label) {
+                remember_window_geometry(&window);
+            }
+        }
+        WindowEvent::CloseRequested { .. } if label != MAIN_WINDOW => {
+            forget_board_window(&app_handle, &label);
+        }
+        _ => {
+            log::trace!("Window {label} event: {event:?}");
+        }
+    });
+}
+
+/// Create the webview of an additional board window
+fn build_board_window<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    label: &str,
+    window_state: &WindowState,
+) -> Result<(), String> {
+    // Mirrors the main window declared in tauri.conf.json
+    let window = tauri::WebviewWindowBuilder::new(app_handle, label, tauri::WebviewUrl::default())
+        .title("Minik")
+        .inner_size(600.0, 60.0)
+        .resizable(false)
+        .always_on_top(true)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .skip_taskbar(true)
+        .build()
+        .map_err(|e| {
+            log::error!("Failed to open window {label}: {e}");
+            e.to_string()
+        })?;
+
+    restore_window_position(&window);
+    watch_window_events(&window);
+
+    log::info!(
+        "Opened window {label} for project {:?}",
+        window_state.selected_project_id
+    );
     Ok(())
 }
 
+/// Open an additional board window showing a project, returning its label
+fn open_board_window<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    project_id: Option<String>,
+) -> Result<String, String> {
+    let state = app_handle.state::<AppStateWrapper>();
+    let mut app_state = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock state: {}", e))?;
+
+    let label = (1..)
+        .map(|n| format!("{BOARD_WINDOW_PREFIX}{n}"))
+        .find(|label| {
+            !app_state.windows.contains_key(label) && app_handle.get_webview_window(label).is_none()
+        })
+        .unwrap_or_default();
+
+    // Cascade new windows from the main one so they do not cover it
+    let offset = 30 * (app_state.windows.len() as i32 + 1);
+    let window_state = WindowState {
+        hidden_columns: project_id
+            .as_ref()
+            .and_then(|id| app_state.project_column_settings.get(id).cloned())
+            .unwrap_or_default(),
+        selected_project_id: project_id,
+        window_x: app_state.main.window_x + offset,
+        window_y: app_state.main.window_y + offset,
+        ..WindowState::default()
+    };
+    app_state
+        .windows
+        .insert(label.clone(), window_state.clone());
+    save_state(&app_state);
+    drop(app_state);
+
+    build_board_window(app_handle, &label, &window_state)?;
+    Ok(label)
+}
+
+/// Reopen the additional board windows saved in the state
+fn restore_board_windows<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let windows: Vec<(String, WindowState)> = match app_handle.state::<AppStateWrapper>().0.lock() {
+        Ok(app_state) => app_state
+            .windows
+            .iter()
+            .map(|(label, w)| (label.clone(), w.clone()))
+            .collect(),
+        Err(e) => {
+            log::error!("Failed to lock state for restoring windows: {e}");
+            return;
+        }
+    };
+
+    for (label, window_state) in windows {
+        if let Err(e) = build_board_window(app_handle, &label, &window_state) {
+            log::error!("Failed to restore window {label}: {e}");
+        }
+    }
+}
+
+/// Stop tracking an additional board window so it is not restored on launch
+fn forget_board_window<R: tauri::Runtime>(app_handle: &AppHandle<R>, label: &str) {
+    match app_handle.state::<AppStateWrapper>().0.lock() {
+        Ok(mut app_state) => {
+            if app_state.windows.remove(label).is_some() {
+                save_state(&app_state);
+                log::info!("Closed window {label}");
+            }
+        }
+        Err(e) => log::error!("Failed to lock state for closing window {label}: {e}"),
+    }
+}
+
+/// Close an additional board window for good
+fn close_board_window<R: tauri::Runtime>(window: &tauri::WebviewWindow<R>) -> Result<(), String> {
+    if window.label() == MAIN_WINDOW {
+        return Err("The main window cannot be closed".to_string());
+    }
+    forget_board_window(window.app_handle(), window.label());
+    window.destroy().map_err(|e| e.to_string())
+}
+
+/// Open another board window, optionally bound to a project
+#[tauri::command]
+async fn open_project_window(
+    project_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    open_board_window(&app_handle, project_id)
+}
+
+/// Close the calling board window, unless it is the main one
+#[tauri::command]
+fn close_window(window: tauri::WebviewWindow) -> Result<(), String> {
+    close_board_window(&window)
+}
+
 fn setup_app_menu<R: tauri::Runtime>(
     app: &mut tauri::App<R>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -759,6 +1904,15 @@ fn setup_app_menu<R: tauri::Runtime>(
         .accelerator("CmdOrCtrl+R")
         .build(app)?;
 
+    let switch_project = MenuItemBuilder::new("Switch Project...")
+        .id("switch-project")
+        .accelerator("CmdOrCtrl+P")
+        .build(app)?;
+
+    let refresh_projects = MenuItemBuilder::new("Refresh Project List")
+        .id("refresh-project-list")
+        .build(app)?;
+
     let toggle_my_items = CheckMenuItem::with_id(
         app,
         "toggle-my-items",
@@ -778,9 +1932,33 @@ fn setup_app_menu<R: tauri::Runtime>(
         .accelerator("CmdOrCtrl+Option+I")
         .build(app)?;
 
+    // Create window management items
+    let new_window = MenuItemBuilder::new("New Window")
+        .id("new-window")
+        .accelerator("CmdOrCtrl+N")
+        .build(app)?;
+
+    let close_window = MenuItemBuilder::new("Close Window")
+        .id("close-window")
+        .accelerator("CmdOrCtrl+W")
+        .build(app)?;
+
+    // Create Edit menu items for undo/redo; the window decides whether they
+    // apply to the text field being edited or to the board
+    let undo = MenuItemBuilder::new("Undo")
+        .id("undo")
+        .accelerator("CmdOrCtrl+Z")
+        .build(app)?;
+
+    let redo = MenuItemBuilder::new("Redo")
+        .id("redo")
+        .accelerator("Shift+CmdOrCtrl+Z")
+        .build(app)?;
+
     // Create View menu
     let view_menu = SubmenuBuilder::new(app, "View")
         .item(&refresh)
+        .item(&refresh_projects)
         .separator()
         .item(&toggle_my_items)
         .item(&toggle_expanded)
@@ -801,6 +1979,8 @@ fn setup_app_menu<R: tauri::Runtime>(
     let project_menu = SubmenuBuilder::new(app, "Project")
         .item(&current_project)
         .item(&select_project)
+        .separator()
+        .item(&switch_project)
         .build()?;
 
     // Create simple Columns menu (dynamic context menus will handle column toggles)
@@ -829,8 +2009,8 @@ fn setup_app_menu<R: tauri::Runtime>(
             .build()?;
 
         let edit_menu = SubmenuBuilder::new(app, "Edit")
-            .item(&PredefinedMenuItem::undo(app, None)?)
-            .item(&PredefinedMenuItem::redo(app, None)?)
+            .item(&undo)
+            .item(&redo)
             .separator()
             .item(&PredefinedMenuItem::cut(app, None)?)
             .item(&PredefinedMenuItem::copy(app, None)?)
@@ -839,6 +2019,9 @@ fn setup_app_menu<R: tauri::Runtime>(
             .build()?;
 
         let window_menu = SubmenuBuilder::new(app, "Window")
+            .item(&new_window)
+            .item(&close_window)
+            .separator()
             .item(&PredefinedMenuItem::minimize(app, None)?)
             .build()?;
 
@@ -860,13 +2043,19 @@ fn setup_app_menu<R: tauri::Runtime>(
     #[cfg(not(target_os = "macos"))]
     {
         let file_menu = SubmenuBuilder::new(app, "File")
+            .item(&new_window)
+            .item(&close_window)
+            .separator()
             .item(&PredefinedMenuItem::quit(app, None)?)
             .build()?;
 
         let edit_menu = SubmenuBuilder::new(app, "Edit")
-            .item(&PredefinedMenuItem::cut(app)?)
-            .item(&PredefinedMenuItem::copy(app)?)
-            .item(&PredefinedMenuItem::paste(app)?)
+            .item(&undo)
+            .item(&redo)
+            .separator()
+            .item(&PredefinedMenuItem::cut(app, None)?)
+            .item(&PredefinedMenuItem::copy(app, None)?)
+            .item(&PredefinedMenuItem::paste(app, None)?)
             .build()?;
 
         let menu = Menu::with_items(app, &[&file_menu, &edit_menu, &view_menu, &project_menu])?;
@@ -881,25 +2070,75 @@ fn setup_app_menu<R: tauri::Runtime>(
         match event.id().as_ref() {
             "refresh" => {
                 log::info!("Refresh menu item selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-refresh", ());
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-refresh", ());
+                }
+            }
+            "new-window" => {
+                log::info!("New window menu item selected");
+                // Start out showing the same project as the window it was opened from
+                let project_id = menu_target(app_handle).and_then(|window| {
+                    app_handle
+                        .state::<AppStateWrapper>()
+                        .0
+                        .lock()
+                        .ok()?
+                        .window(window.label())
+                        .selected_project_id
+                        .clone()
+                });
+                if let Err(e) = open_board_window(app_handle, project_id) {
+                    log::error!("Failed to open new window: {e}");
+                }
+            }
+            "close-window" => {
+                log::info!("Close window menu item selected");
+                if let Some(window) = menu_target(app_handle) {
+                    if let Err(e) = close_board_window(&window) {
+                        log::warn!("Not closing window {}: {e}", window.label());
+                    }
+                }
+            }
+            "switch-project" => {
+                log::info!("Switch project menu item selected");
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-switch-project", ());
+                }
+            }
+            "refresh-project-list" => {
+                log::info!("Refresh project list menu item selected");
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let _ = refresh_catalog(&app_handle).await;
+                });
+            }
+            "undo" => {
+                log::info!("Undo menu item selected");
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-undo", ());
+                }
+            }
+            "redo" => {
+                log::info!("Redo menu item selected");
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-redo", ());
                 }
             }
             "toggle-my-items" => {
                 log::info!("Toggle my items menu item selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-toggle-my-items", ());
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-toggle-my-items", ());
                 }
             }
             "toggle-expanded" => {
                 log::info!("Toggle expanded menu item selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-toggle-expanded", ());
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-toggle-expanded", ());
                 }
             }
             "open-devtools" => {
                 log::info!("Open developer tools menu item selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
+                if let Some(window) = menu_target(app_handle) {
                     #[cfg(debug_assertions)]
                     {
                         window.open_devtools();
@@ -913,34 +2152,34 @@ fn setup_app_menu<R: tauri::Runtime>(
             }
             "select-project" => {
                 log::info!("Select project menu item selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-select-project", ());
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-select-project", ());
                 }
             }
             id if id.starts_with("project-") => {
                 log::info!("Project selected: {id}");
                 let project_id = id.strip_prefix("project-").unwrap_or("").to_string();
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-project-selected", project_id);
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-project-selected", project_id);
                 }
             }
             "columns-show-all" => {
                 log::info!("Show all columns selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-columns-show-all", ());
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-columns-show-all", ());
                 }
             }
             "columns-hide-all" => {
                 log::info!("Hide all columns selected");
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-columns-hide-all", ());
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-columns-hide-all", ());
                 }
             }
             id if id.starts_with("column-") => {
                 log::info!("Column visibility toggle: {id}");
                 let column_id = id.strip_prefix("column-").unwrap_or("").to_string();
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.emit("menu-toggle-column", column_id);
+                if let Some(window) = menu_target(app_handle) {
+                    let _ = window.emit_to(window.label(), "menu-toggle-column", column_id);
                 }
             }
             _ => {}
@@ -962,18 +2201,415 @@ fn update_column_menu<R: tauri::Runtime>(
     Ok(())
 }
 
+/// Project given with `--project`, shown in the main window once it asks for one
+struct LaunchProject(Mutex<Option<String>>);
+
+/// Bring a window to the front
+fn focus_window(window: &tauri::WebviewWindow) {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Select a project in a window and have the window load it
+fn show_project_in_window(
+    app_handle: &AppHandle,
+    window: &tauri::WebviewWindow,
+    project_id: String,
+) -> Result<(), String> {
+    select_project_in_window(app_handle, window, project_id.clone()).map_err(|e| {
+        log::error!("Failed to select project {project_id}: {e}");
+        e
+    })?;
+    let _ = window.emit_to(
+        window.label(),
+        "project-selected",
+        serde_json::json!({ "projectId": project_id }),
+    );
+    Ok(())
+}
+
+/// Apply the arguments of a later launch to the running instance
+fn handle_second_launch(app_handle: &AppHandle, argv: Vec<String>) {
+    log::info!("Another launch forwarded its arguments: {argv:?}");
+    let Some(window) = app_handle.get_webview_window(MAIN_WINDOW) else {
+        log::error!("Main window not found for forwarded arguments");
+        return;
+    };
+    focus_window(&window);
+
+    let args = match cli::parse(&argv) {
+        Ok(args) => args,
+        Err(e) => {
+            log::warn!("Ignoring invalid forwarded arguments: {e}");
+            return;
+        }
+    };
+    for arg in &args.ignored {
+        log::warn!("Ignoring unknown forwarded argument {arg:?}");
+    }
+    if args.config_dir.is_some()
+        || args.account.is_some()
+        || args.record.is_some()
+        || args.replay.is_some()
+    {
+        log::warn!(
+            "--config-dir, --account, --record and --replay only apply when minik starts, ignoring them"
+        );
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Some(project) = args.project {
+            if let Some(project_id) = resolve_project(&app_handle, &project).await {
+                let _ = show_project_in_window(&app_handle, &window, project_id);
+            }
+        }
+
+        let is_expanded = app_handle
+            .state::<AppStateWrapper>()
+            .0
+            .lock()
+            .is_ok_and(|app_state| app_state.main.is_expanded);
+        if args.expanded && !is_expanded {
+            let _ = window.emit_to(MAIN_WINDOW, "menu-toggle-expanded", ());
+        }
+    });
+}
+
+/// Open the project of a `minik://` link, in a window already showing it if there is one
+fn open_deep_link(app_handle: &AppHandle, url: &tauri::Url) {
+    let link = match DeepLink::parse(url) {
+        Ok(link) => link,
+        Err(e) => {
+            log::warn!("Ignoring deep link: {e}");
+            return;
+        }
+    };
+    log::info!("Opening deep link {url}");
+    if let Some(view) = link.view {
+        log::debug!("Link is for project view {view}, minik always shows the Status columns");
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(project_id) = resolve_project(&app_handle, &link.project).await else {
+            return;
+        };
+
+        let state = app_handle.state::<AppStateWrapper>();
+        let label = {
+            let Ok(mut app_state) = state.0.lock() else {
+                log::error!("Failed to lock state for deep link");
+                return;
+            };
+            let label = app_state
+                .all_windows()
+                .find(|(_, w)| w.selected_project_id.as_ref() == Some(&project_id))
+                .map_or(MAIN_WINDOW, |(label, _)| label)
+                .to_string();
+            if let Some(mine) = link.only_my_items() {
+                app_state.window_mut(&label).show_only_my_items = mine;
+                save_state(&app_state);
+            }
+            label
+        };
+
+        let Some(window) = app_handle.get_webview_window(&label) else {
+            log::error!("Window {label} not found for deep link");
+            return;
+        };
+        if let Some(mine) = link.only_my_items() {
+            let _ = window.emit_to(window.label(), "my-items-changed", mine);
+        }
+        if show_project_in_window(&app_handle, &window, project_id).is_ok() {
+            focus_window(&window);
+        }
+    });
+}
+
+/// Parameters of control requests about a window, the main one by default
+#[derive(Deserialize)]
+struct ControlWindowParams {
+    window: Option<String>,
+    /// Fetch the board from GitHub instead of answering from memory
+    #[serde(default)]
+    fresh: bool,
+}
+
+#[derive(Deserialize)]
+struct ControlSelectParams {
+    /// Project as `org/number` or project ID
+    project: String,
+    window: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ControlMoveParams {
+    item_id: String,
+    column_id: String,
+    /// Defaults to the project shown in the window
+    project_id: Option<String>,
+    window: Option<String>,
+}
+
+/// Find the window a control request is about
+fn control_window(
+    app_handle: &AppHandle,
+    label: Option<String>,
+) -> Result<tauri::WebviewWindow, ControlError> {
+    let label = label.unwrap_or_else(|| MAIN_WINDOW.to_string());
+    app_handle
+        .get_webview_window(&label)
+        .ok_or_else(|| ControlError::InvalidParams(format!("No window {label:?}")))
+}
+
+fn to_control_value<T: Serialize>(value: T) -> Result<serde_json::Value, ControlError> {
+    serde_json::to_value(value).map_err(|e| ControlError::Failed(e.to_string()))
+}
+
+/// Answer a request from the control channel through the command handlers
+async fn handle_control_request(
+    app_handle: AppHandle,
+    method: String,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, ControlError> {
+    match method.as_str() {
+        "current_project" => {
+            let params: ControlWindowParams = control::params(params)?;
+            let window = control_window(&app_handle, params.window)?;
+            to_control_value(current_project(app_handle, window).await)
+        }
+        "board" => {
+            let params: ControlWindowParams = control::params(params)?;
+            let window = control_window(&app_handle, params.window)?;
+            let Some(project_id) = current_project(app_handle.clone(), window.clone()).await else {
+                return Ok(serde_json::Value::Null);
+            };
+
+            let cached = if params.fresh {
+                None
+            } else {
+                cached_project_data(
+                    project_id.clone(),
+                    app_handle.state(),
+                    app_handle.state(),
+                    window.clone(),
+                )
+            };
+            let data = match cached {
+                Some(data) => data,
+                None => {
+                    project_data(
+                        project_id,
+                        app_handle.state(),
+                        app_handle.state(),
+                        app_handle.clone(),
+                        window,
+                    )
+                    .await?
+                }
+            };
+            to_control_value(data)
+        }
+        "select_project" => {
+            let params: ControlSelectParams = control::params(params)?;
+            let window = control_window(&app_handle, params.window)?;
+            let project_id = resolve_project(&app_handle, &params.project)
+                .await
+                .ok_or_else(|| format!("Project {} not found", params.project))?;
+            show_project_in_window(&app_handle, &window, project_id.clone())?;
+            to_control_value(project_id)
+        }
+        "move_item" => {
+            let params: ControlMoveParams = control::params(params)?;
+            let project_id = match params.project_id {
+                Some(project_id) => project_id,
+                None => {
+                    let window = control_window(&app_handle, params.window)?;
+                    current_project(app_handle.clone(), window)
+                        .await
+                        .ok_or_else(|| "No project selected".to_string())?
+                }
+            };
+            let from_column_id = app_handle
+                .state::<KnownItems>()
+                .get(&params.item_id)
+                .map(|known| known.column_id);
+
+            let outcome = update_item_column(
+                project_id.clone(),
+                params.item_id.clone(),
+                params.column_id,
+                None,
+                app_handle.state(),
+                app_handle.clone(),
+            )
+            .await?;
+
+            // Windows showing the board only learn about moves made elsewhere from events
+            if let (MoveOutcome::Applied, Some(from_column_id)) = (&outcome, from_column_id) {
+                let item = app_handle
+                    .state::<BoardStore>()
+                    .get(&project_id)
+                    .and_then(|board| board.items.into_iter().find(|i| i.id == params.item_id));
+                if let Some(item) = item {
+                    let change = ItemChange::Moved {
+                        item,
+                        from_column_id,
+                    };
+                    let _ = app_handle.emit(
+                        change.event_name(),
+                        ItemEvent {
+                            project_id: &project_id,
+                            change: &change,
+                        },
+                    );
+                }
+            }
+            to_control_value(outcome)
+        }
+        "toggle_expanded" => {
+            let params: ControlWindowParams = control::params(params)?;
+            let window = control_window(&app_handle, params.window)?;
+            let expanded = is_expanded(app_handle.state(), window.clone());
+            // The window resizes itself, so let it run the toggle like the menu does
+            let _ = window.emit_to(window.label(), "menu-toggle-expanded", ());
+            to_control_value(!expanded)
+        }
+        "refresh" => {
+            let params: ControlWindowParams = control::params(params)?;
+            let window = control_window(&app_handle, params.window)?;
+            let _ = window.emit_to(window.label(), "menu-refresh", ());
+            Ok(serde_json::Value::Null)
+        }
+        _ => Err(ControlError::UnknownMethod(method)),
+    }
+}
+
+/// Keeps the config file watched for as long as the app runs
+struct ConfigWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/// Keeps the local board files watched, replaced whenever the list of boards changes
+#[derive(Default)]
+struct LocalBoardWatcher(Mutex<Option<notify::RecommendedWatcher>>);
+
+/// Watch the local board files and refresh a board whenever its file changes
+fn watch_local_boards(app_handle: &AppHandle, paths: &[String]) {
+    let handle = app_handle.clone();
+    let watcher = local::watch_boards(paths, move |project_id| {
+        let app_handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            refresh_board(&app_handle, &project_id).await;
+        });
+    });
+    if let Ok(mut current) = app_handle.state::<LocalBoardWatcher>().0.lock() {
+        *current = watcher;
+    }
+}
+
+/// Set up the board backends, GitHub first
+fn board_backends(app_handle: &AppHandle) -> Backends {
+    let mut backends = Backends::github();
+    let handle = app_handle.clone();
+    backends.register(
+        local::PREFIX,
+        Arc::new(move || {
+            let boards = handle.state::<ConfigStore>().get().local_boards;
+            Ok(Arc::new(LocalBackend::new(&boards)))
+        }),
+    );
+    let handle = app_handle.clone();
+    backends.register(
+        gitlab::PREFIX,
+        Arc::new(move || {
+            let config = handle
+                .state::<ConfigStore>()
+                .get()
+                .gitlab
+                .ok_or_else(|| anyhow::anyhow!("GitLab is not configured"))?;
+            Ok(Arc::new(GitLabClient::new(&config)?))
+        }),
+    );
+    let handle = app_handle.clone();
+    backends.register(
+        gitea::PREFIX,
+        Arc::new(move || {
+            let config = handle
+                .state::<ConfigStore>()
+                .get()
+                .gitea
+                .ok_or_else(|| anyhow::anyhow!("Gitea is not configured"))?;
+            Ok(Arc::new(GiteaClient::new(&config)?))
+        }),
+    );
+    backends
+}
+
+/// Apply a reloaded config and tell every window about it
+fn apply_config(app_handle: &AppHandle, config: Config) {
+    logging::set_level(config.log_level());
+    watch_local_boards(app_handle, &config.local_boards);
+    let _ = app_handle.emit("config-changed", config);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = cli::parse(std::env::args_os()).unwrap_or_else(|e| e.exit());
+    if let Some(dir) = args.config_dir.clone() {
+        config::set_config_dir(dir);
+    }
+    if let Some(account) = args.account.clone() {
+        github::set_account(account);
+    }
+
     // Initialize logging first
     if let Err(e) = logging::init_logging() {
         eprintln!("Failed to initialize logging: {e}");
     }
 
     log::info!("Starting Minik application");
-    let state = load_state();
+    log::debug!("Launch arguments: {args:?}");
+    for arg in &args.ignored {
+        log::warn!("Ignoring unknown launch argument {arg:?}");
+    }
+    let traffic = match (&args.record, &args.replay) {
+        (Some(dir), _) => traffic::record(dir.clone()),
+        (_, Some(dir)) => traffic::replay(dir),
+        _ => Ok(()),
+    };
+    if let Err(e) = traffic {
+        log::error!("Failed to set up GitHub traffic recording: {e:#}");
+        return Err(e.into());
+    }
+    let config = ConfigStore::load();
+    logging::set_level(config.get().log_level());
+    let mut state = load_state();
+    if args.expanded {
+        state.main.is_expanded = true;
+    }
+    let mutation_queue = MutationQueue::load();
 
     tauri::Builder::default()
+        // Must come first so a second launch exits before setting anything up
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            handle_second_launch(app, argv);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .manage(config)
+        .manage(LaunchProject(Mutex::new(args.project)))
         .manage(AppStateWrapper(Mutex::new(state)))
+        .manage(mutation_queue)
+        .manage(KnownItems::default())
+        .manage(History::default())
+        .manage(BoardStore::default())
+        .manage(InFlight::<ProjectData>::default())
+        .manage(ProjectRequests::default())
+        .manage(LocalBoardWatcher::default())
+        .manage(CatalogStore::load())
+        .manage(InFlight::<Catalog>::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(tauri::generate_handler![
@@ -981,7 +2617,16 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             list_organizations,
             list_org_projects,
             project_data,
+            cached_project_data,
             update_item_column,
+            pending_mutations,
+            update_item_field_value,
+            assign_item,
+            archive_item,
+            create_item,
+            delete_item,
+            undo,
+            redo,
             toggle_expanded,
             resize_window_for_columns,
             resize_window_with_height,
@@ -989,6 +2634,7 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             resize_for_context_menu,
             select_project,
             current_project,
+            app_config,
             toggle_my_items,
             toggle_column_visibility,
             hide_column,
@@ -1001,71 +2647,93 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             update_columns_menu,
             show_project_context_menu,
             show_column_context_menu,
+            project_catalog,
+            refresh_project_list,
+            search_projects,
+            toggle_favorite_project,
+            open_project_window,
+            close_window,
         ])
         .setup(|app| {
             let _app_handle = app.handle().clone();
+            app.manage(board_backends(app.handle()));
+
+            // Replay moves left over from an offline session once their backend is reachable
+            if let Some(queue) = app.try_state::<MutationQueue>() {
+                for project_id in queue.project_ids() {
+                    watch_connectivity(app.handle().clone(), &project_id);
+                }
+            }
+
+            // Open minik:// links, both the one we were launched with and later ones
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            if let Err(e) = app.deep_link().register_all() {
+                log::warn!(
+                    "Failed to register the {} URL scheme: {e}",
+                    deeplink::SCHEME
+                );
+            }
+            let app_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    open_deep_link(&app_handle, &url);
+                }
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    open_deep_link(app.handle(), &url);
+                }
+            }
+
+            // Let scripts and editor plugins drive the running app
+            let app_handle = app.handle().clone();
+            control::serve(Arc::new(move |method, params| {
+                Box::pin(handle_control_request(app_handle.clone(), method, params))
+            }));
+
+            // Apply edits to config.toml while the app is running
+            if let Some(watcher) = config::watch_config(app.handle().clone(), apply_config) {
+                app.manage(ConfigWatcher { _watcher: watcher });
+            }
+
+            // Pick up edits made to local boards in other editors
+            watch_local_boards(app.handle(), &app.state::<ConfigStore>().get().local_boards);
+
+            // The backend owns board polling and pushes changes to the UI
+            spawn_board_refresher(app.handle().clone());
+
+            // Have the project list ready before the first right-click
+            if app
+                .try_state::<CatalogStore>()
+                .is_some_and(|store| store.get().is_none_or(|c| c.is_expired()))
+            {
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let _ = refresh_catalog(&app_handle).await;
+                });
+            }
 
             // Build the application menu
             setup_app_menu(app)?;
 
             let window = app
-                .get_webview_window("main")
+                .get_webview_window(MAIN_WINDOW)
                 .ok_or_else(|| "Failed to get main window".to_string())?;
+            restore_window_position(&window);
+            watch_window_events(&window);
 
-            // Restore window position from state
-            if let Some(state_wrapper) = app.try_state::<AppStateWrapper>() {
-                if let Ok(app_state) = state_wrapper.0.lock() {
-                    if app_state.window_x != 100 || app_state.window_y != 50 {
-                        let _ = window.set_position(PhysicalPosition::new(
-                            app_state.window_x,
-                            app_state.window_y,
-                        ));
-                        log::info!(
-                            "Restored window position to ({}, {})",
-                            app_state.window_x,
-                            app_state.window_y
-                        );
-                    }
-                } else {
-                    log::error!("Failed to lock state for window position restoration");
-                }
-            }
-
-            let app_handle_clone = app.handle().clone();
-            window.on_window_event(move |event| {
-                match event {
-                    WindowEvent::Focused(false) => {
-                        log::debug!("Window lost focus");
-                    }
-                    WindowEvent::Focused(true) => {
-                        log::debug!("Window gained focus");
-                    }
-                    WindowEvent::Resized(size) => {
-                        log::debug!("Window resized to: {size:?}");
-                    }
-                    WindowEvent::Moved(position) => {
-                        log::debug!("Window moved to: {position:?}");
-                        // Save window position
-                        if let Some(state_wrapper) = app_handle_clone.try_state::<AppStateWrapper>()
-                        {
-                            if let Ok(mut app_state) = state_wrapper.0.lock() {
-                                app_state.window_x = position.x;
-                                app_state.window_y = position.y;
-                                save_state(&app_state);
-                            } else {
-                                log::error!("Failed to lock state for window position save");
-                            }
-                        }
-                    }
-                    _ => {
-                        log::trace!("Window event: {event:?}");
-                    }
-                }
-            });
+            // Bring back the extra board windows of the previous session
+            restore_board_windows(app.handle());
 
             Ok(())
         })
-        .run(tauri::generate_context!())?;
+        .build(tauri::generate_context!())?
+        .run(|_app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Do not lose changes still waiting for the debounced save
+                state::flush_state();
+            }
+        });
 
     log::info!("Minik application shutting down");
     Ok(())
@@ -0,0 +1,470 @@
+//! Boards kept in local JSON or Markdown files
+//!
+//! Lets the sticky window double as a personal Kanban for work that is not on
+//! GitHub. The files are listed in `local_boards` in `config.toml`. A `.json`
+//! file holds the columns and items in the same shape as GitHub boards; a `.md`
+//! file is an Obsidian Kanban board (see [`crate::markdown`]). Files are read
+//! on every request and watched for edits made elsewhere.
+
+use crate::backend::BoardBackend;
+use crate::github::{
+    ItemFieldState, Organization, Project, ProjectColumn, ProjectData, ProjectItem,
+};
+use crate::markdown::{parse_card, MarkdownBoard};
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Prefix of the organization and project IDs of local boards
+pub const PREFIX: &str = "local";
+
+/// Organization holding every local board
+const ORGANIZATION: &str = "local:boards";
+
+/// Local boards have a single status field, their columns
+const STATUS_FIELD_ID: &str = "status";
+
+/// Columns of a board whose file does not exist yet
+const DEFAULT_COLUMNS: &[&str] = &["To do", "In progress", "Done"];
+
+/// Serializes changes so two writers never interleave on a file
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Hash of what each board file held when minik last wrote or reloaded it
+static KNOWN_CONTENT: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+/// File formats a board can be stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Markdown,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("md" | "markdown") => Ok(Self::Markdown),
+            _ => anyhow::bail!("{path:?} is neither a .json nor a .md file"),
+        }
+    }
+}
+
+/// Check whether a file can hold a local board
+pub fn is_board_file(path: &Path) -> bool {
+    Format::of(path).is_ok()
+}
+
+/// Expand `~/` and make relative paths relative to the config directory
+pub fn expand_path(path: &str) -> PathBuf {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(path),
+    };
+    if path.is_relative() {
+        if let Some(dir) = crate::config::config_dir() {
+            return dir.join(path);
+        }
+    }
+    path
+}
+
+/// Get the project ID of the board stored in a file
+pub fn project_id(path: &Path) -> String {
+    format!("{PREFIX}:{}", path.display())
+}
+
+/// Contents of a JSON board file, and what every board file is read into
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Board {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    columns: Vec<ProjectColumn>,
+    #[serde(default)]
+    items: Vec<ProjectItem>,
+}
+
+impl Board {
+    /// Board for a JSON file that does not exist yet
+    fn empty() -> Self {
+        Self {
+            title: None,
+            columns: DEFAULT_COLUMNS
+                .iter()
+                .map(|name| ProjectColumn {
+                    id: (*name).to_string(),
+                    items_count: 0,
+                    name: (*name).to_string(),
+                })
+                .collect(),
+            items: Vec::new(),
+        }
+    }
+
+    fn column(&self, column_id: &str) -> Result<&ProjectColumn> {
+        self.columns
+            .iter()
+            .find(|c| c.id == column_id)
+            .with_context(|| format!("No column {column_id:?} on this board"))
+    }
+
+    fn item_index(&self, item_id: &str) -> Result<usize> {
+        self.items
+            .iter()
+            .position(|i| i.id == item_id)
+            .with_context(|| format!("Item {item_id} is not on this board"))
+    }
+}
+
+/// A board file as read from disk
+enum BoardFile {
+    Json(Board),
+    Markdown(MarkdownBoard),
+}
+
+impl BoardFile {
+    /// Get the title, columns and items of the board
+    fn board(&self) -> Board {
+        let mut board = match self {
+            Self::Json(board) => board.clone(),
+            Self::Markdown(markdown) => Board {
+                title: markdown.title(),
+                columns: markdown.columns(),
+                items: markdown.items(),
+            },
+        };
+        for column in &mut board.columns {
+            column.items_count = board
+                .items
+                .iter()
+                .filter(|i| i.column_id == column.id)
+                .count();
+        }
+        board
+    }
+
+    /// Move an item to the bottom of another column, returning its new version
+    fn move_item(&mut self, item_id: &str, column_id: &str) -> Result<Option<String>> {
+        match self {
+            Self::Json(board) => {
+                board.column(column_id)?;
+                let mut item = board.items.remove(board.item_index(item_id)?);
+                let updated_at = chrono::Utc::now().to_rfc3339();
+                item.column_id = column_id.to_string();
+                item.updated_at = Some(updated_at.clone());
+                board.items.push(item);
+                Ok(Some(updated_at))
+            }
+            Self::Markdown(markdown) => {
+                markdown.move_item(item_id, column_id)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Add a card to the bottom of a column
+    fn create_item(&mut self, column_id: &str, text: &str) -> Result<ProjectItem> {
+        match self {
+            Self::Json(board) => {
+                board.column(column_id)?;
+                let (title, labels, assignees) = parse_card(text);
+                let item = ProjectItem {
+                    id: format!("item-{:016x}", rand::random::<u64>()),
+                    title,
+                    assignees,
+                    column_id: column_id.to_string(),
+                    labels,
+                    url: None,
+                    updated_at: Some(chrono::Utc::now().to_rfc3339()),
+                    content_id: None,
+                };
+                board.items.push(item.clone());
+                Ok(item)
+            }
+            Self::Markdown(markdown) => markdown.create_item(column_id, text),
+        }
+    }
+
+    fn delete_item(&mut self, item_id: &str) -> Result<()> {
+        match self {
+            Self::Json(board) => {
+                board.items.remove(board.item_index(item_id)?);
+                Ok(())
+            }
+            Self::Markdown(markdown) => markdown.delete_item(item_id),
+        }
+    }
+
+    fn render(&self) -> Result<String> {
+        match self {
+            Self::Json(board) => Ok(serde_json::to_string_pretty(board)?),
+            Self::Markdown(markdown) => Ok(markdown.render()),
+        }
+    }
+}
+
+/// Read a board file, or start an empty board if it does not exist yet
+fn load(path: &Path) -> Result<BoardFile> {
+    let format = Format::of(path)?;
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("Board file {path:?} does not exist yet");
+            return Ok(match format {
+                Format::Json => BoardFile::Json(Board::empty()),
+                Format::Markdown => BoardFile::Markdown(MarkdownBoard::new(path, DEFAULT_COLUMNS)),
+            });
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {path:?}")),
+    };
+
+    Ok(match format {
+        Format::Json => BoardFile::Json(
+            serde_json::from_str(&text).with_context(|| format!("Failed to parse {path:?}"))?,
+        ),
+        Format::Markdown => BoardFile::Markdown(MarkdownBoard::parse(path, &text)),
+    })
+}
+
+/// Get the file a board is written to before it replaces the board
+fn temp_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_string_lossy();
+    Some(path.with_file_name(format!(".{file_name}.tmp")))
+}
+
+/// Remember what a board file holds now, returning whether that is news to minik
+fn remember_content(path: &Path, text: &str) -> bool {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    let hash = hasher.finish();
+    match KNOWN_CONTENT.lock() {
+        Ok(mut known) => known.insert(path.to_path_buf(), hash) != Some(hash),
+        Err(_) => true,
+    }
+}
+
+/// Write a board file, replacing it in one step so watchers never see half a file
+fn save(path: &Path, file: &BoardFile) -> Result<()> {
+    let text = file.render()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}"))?;
+    }
+
+    let temp_path = temp_path(path).context("Board path has no file name")?;
+    std::fs::write(&temp_path, &text).with_context(|| format!("Failed to write {temp_path:?}"))?;
+    // Known before the rename, so the watcher does not reload what we just wrote
+    remember_content(path, &text);
+    std::fs::rename(&temp_path, path).with_context(|| format!("Failed to replace {path:?}"))?;
+    debug!("Saved board to {path:?}");
+    Ok(())
+}
+
+/// Load a board, change it and save it again while holding the write lock
+fn update<T>(path: &Path, change: impl FnOnce(&mut BoardFile) -> Result<T>) -> Result<T> {
+    let _guard = WRITE_LOCK
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock local boards: {e}"))?;
+    let mut file = load(path)?;
+    let result = change(&mut file)?;
+    save(path, &file)?;
+    Ok(result)
+}
+
+/// Backend for the board files listed in `config.toml`
+pub struct LocalBackend {
+    paths: Vec<PathBuf>,
+}
+
+impl LocalBackend {
+    /// Create a backend for the given board files
+    pub fn new(paths: &[String]) -> Self {
+        Self {
+            paths: paths.iter().map(|p| expand_path(p)).collect(),
+        }
+    }
+
+    /// Get the file of a project, refusing files that are not configured
+    fn path(&self, project_id: &str) -> Result<&Path> {
+        let path = project_id
+            .strip_prefix(PREFIX)
+            .and_then(|p| p.strip_prefix(':'))
+            .with_context(|| format!("{project_id:?} is not a local board"))?;
+        self.paths
+            .iter()
+            .find(|p| p.as_path() == Path::new(path))
+            .map(PathBuf::as_path)
+            .with_context(|| format!("{path:?} is not listed in local_boards"))
+    }
+
+    fn project(&self, index: usize, path: &Path, board: &Board) -> Project {
+        let title = board.title.clone().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string())
+        });
+        Project {
+            id: project_id(path),
+            title,
+            url: format!("file://{}", path.display()),
+            number: index as u64 + 1,
+        }
+    }
+}
+
+#[async_trait]
+impl BoardBackend for LocalBackend {
+    fn name(&self) -> &str {
+        "Local"
+    }
+
+    async fn list_organizations(&self) -> Result<Vec<Organization>> {
+        if self.paths.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![Organization {
+            id: 0,
+            login: ORGANIZATION.to_string(),
+            name: Some("Local boards".to_string()),
+        }])
+    }
+
+    async fn list_projects(&self, _org: &str) -> Result<Vec<Project>> {
+        Ok(self
+            .paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let board = load(path).map(|f| f.board()).unwrap_or_else(|e| {
+                    warn!("Failed to read local board {path:?}: {e:#}");
+                    Board::default()
+                });
+                self.project(index, path, &board)
+            })
+            .collect())
+    }
+
+    async fn project_data(&self, project_id: &str) -> Result<ProjectData> {
+        let path = self.path(project_id)?;
+        let index = self
+            .paths
+            .iter()
+            .position(|p| p == path)
+            .unwrap_or_default();
+        let board = load(path)?.board();
+
+        Ok(ProjectData {
+            project: self.project(index, path, &board),
+            columns: board.columns,
+            hidden_columns: Vec::new(),
+            items: board.items,
+            status_field_id: STATUS_FIELD_ID.to_string(),
+            fetched_at: Some(chrono::Utc::now()),
+            stale: false,
+        })
+    }
+
+    async fn item_field_state(&self, item_id: &str, _field_id: &str) -> Result<ItemFieldState> {
+        for path in &self.paths {
+            let board = load(path)?.board();
+            if let Some(item) = board.items.into_iter().find(|i| i.id == item_id) {
+                return Ok(ItemFieldState {
+                    option_id: Some(item.column_id),
+                    updated_at: item.updated_at,
+                });
+            }
+        }
+        anyhow::bail!("Item {item_id} no longer exists")
+    }
+
+    async fn move_item(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        _field_id: &str,
+        column_id: &str,
+    ) -> Result<Option<String>> {
+        let path = self.path(project_id)?;
+        info!("Moving local item {item_id} to column {column_id} in {path:?}");
+        update(path, |file| file.move_item(item_id, column_id))
+    }
+
+    async fn create_item(
+        &self,
+        project_id: &str,
+        column_id: &str,
+        title: &str,
+    ) -> Result<ProjectItem> {
+        let path = self.path(project_id)?;
+        let title = title.trim();
+        if parse_card(title).0.is_empty() {
+            anyhow::bail!("A card needs a title");
+        }
+        info!("Adding local item {title:?} to column {column_id} in {path:?}");
+        update(path, |file| file.create_item(column_id, title))
+    }
+
+    async fn delete_item(&self, project_id: &str, item_id: &str) -> Result<()> {
+        let path = self.path(project_id)?;
+        info!("Deleting local item {item_id} from {path:?}");
+        update(path, |file| file.delete_item(item_id))
+    }
+}
+
+/// Watch board files and call `on_change` with the project ID of every edited board
+///
+/// The watcher stops when the returned value is dropped.
+pub fn watch_boards<F>(paths: &[String], on_change: F) -> Option<RecommendedWatcher>
+where
+    F: Fn(String) + Send + 'static,
+{
+    let files: Vec<PathBuf> = paths.iter().map(|p| expand_path(p)).collect();
+    if files.is_empty() {
+        return None;
+    }
+
+    // Editors often replace files instead of writing to them, so watch the directories
+    let dirs: BTreeSet<PathBuf> = files
+        .iter()
+        .filter_map(|f| f.parent().map(Path::to_path_buf))
+        .collect();
+    let watched = files.clone();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) => {
+                for path in watched.iter().filter(|f| event.paths.contains(f)) {
+                    // Our own saves rename a temp file over the board
+                    if temp_path(path).is_some_and(|temp| event.paths.contains(&temp)) {
+                        continue;
+                    }
+                    if let Ok(text) = std::fs::read_to_string(path) {
+                        if !remember_content(path, &text) {
+                            debug!("Local board {path:?} unchanged, not reloading");
+                            continue;
+                        }
+                    }
+                    debug!("Local board {path:?} changed: {:?}", event.kind);
+                    on_change(project_id(path));
+                }
+            }
+            Err(e) => warn!("Local board watcher error: {e}"),
+        })
+        .map_err(|e| error!("Failed to create local board watcher: {e}"))
+        .ok()?;
+
+    for dir in &dirs {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            warn!("Failed to create {dir:?}: {e}");
+        }
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            error!("Failed to watch {dir:?}: {e}");
+        }
+    }
+    info!("Watching {} local boards for changes", files.len());
+    Some(watcher)
+}
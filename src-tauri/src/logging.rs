@@ -62,11 +62,12 @@ pub fn init_logging() -> Result<()> {
             Root::builder()
                 .appender("console")
                 .appender("file")
-                .build(LevelFilter::Info),
+                .build(LevelFilter::Trace),
         )?;
 
-    // Initialize log4rs
+    // Initialize log4rs; the level is filtered globally so it can change at runtime
     log4rs::init_config(config)?;
+    log::set_max_level(LevelFilter::Info);
 
     log::info!("===========================================");
     log::info!("Minik application started");
@@ -78,6 +79,14 @@ pub fn init_logging() -> Result<()> {
     Ok(())
 }
 
+/// Change which messages are logged from now on
+pub fn set_level(level: LevelFilter) {
+    if log::max_level() != level {
+        log::info!("Log level set to {level}");
+        log::set_max_level(level);
+    }
+}
+
 /// Get the appropriate log directory for the current platform
 fn get_log_directory() -> Result<PathBuf> {
     let log_dir = if cfg!(target_os = "macos") {
@@ -0,0 +1,671 @@
+//! Markdown boards in the Obsidian Kanban format
+//!
+//! A `##` heading starts a lane and every `- [ ]` list item below it is a card.
+//! Lanes marked `**Complete**` hold checked cards. The file is kept as lines
+//! and changed in place, so moving a card only touches the lines of that card
+//! and the front matter, archive, settings and any other text survive as they
+//! were written.
+//!
+//! Card IDs come from the card title, numbered among cards of the same title.
+//! A card whose number would shift when another card moves, is added or is
+//! deleted gets its ID pinned with an Obsidian block ID (`^id`) at the end of
+//! its line, which takes precedence over the title.
+
+use crate::github::{ProjectColumn, ProjectItem};
+use anyhow::{Context as _, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Marker line of lanes whose cards are done
+const COMPLETE_MARKER: &str = "**Complete**";
+
+/// Line separating the lanes from the archived cards
+const ARCHIVE_MARKER: &str = "***";
+
+/// Start of the settings block Obsidian appends to the file
+const SETTINGS_MARKER: &str = "%% kanban:settings";
+
+/// A lane and the lines it spans
+struct Lane {
+    name: String,
+    heading: usize,
+    /// Line after the last line of the lane
+    end: usize,
+    complete: bool,
+}
+
+/// A card and the lines it spans, including indented continuation lines
+struct Card {
+    lane: usize,
+    start: usize,
+    end: usize,
+    /// Text of the first line, without the block ID
+    text: String,
+    block: Option<String>,
+}
+
+/// Everything found in a board file
+struct Layout {
+    title: Option<String>,
+    lanes: Vec<Lane>,
+    cards: Vec<Card>,
+}
+
+/// Stable ID for a card, which has no room for one in the file
+fn card_id(path: &Path, title: &str, occurrence: usize) -> String {
+    // FNV-1a, so IDs survive restarts and compiler upgrades
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in format!("{}\0{title}\0{occurrence}", path.display()).bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("md-{hash:016x}")
+}
+
+/// Split an Obsidian block ID (`^id`) off the end of card text
+fn split_block_id(text: &str) -> (&str, Option<&str>) {
+    let trimmed = text.trim_end();
+    let (rest, last) = trimmed.rsplit_once(' ').unwrap_or(("", trimmed));
+    match last.strip_prefix('^') {
+        Some(id) if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') => {
+            (rest.trim_end(), Some(id))
+        }
+        _ => (text, None),
+    }
+}
+
+/// Split card text into its title, `#labels` and `@assignees`
+///
+/// Obsidian dates (`@{2024-05-01}`) and times (`@@{10:00}`) stay in the title.
+pub fn parse_card(text: &str) -> (String, Vec<String>, Vec<String>) {
+    let mut title = Vec::new();
+    let mut labels = Vec::new();
+    let mut assignees = Vec::new();
+    for word in text.split_whitespace() {
+        let is_name = |name: &str| name.starts_with(|c: char| c.is_alphanumeric());
+        match (word.strip_prefix('#'), word.strip_prefix('@')) {
+            (Some(label), _) if is_name(label) => labels.push(label.to_string()),
+            (_, Some(login)) if is_name(login) => assignees.push(login.to_string()),
+            _ => title.push(word),
+        }
+    }
+    (title.join(" "), labels, assignees)
+}
+
+/// Get the text of a card line without its bullet and checkbox
+fn card_text(line: &str) -> Option<&str> {
+    let item = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))?;
+    Some(
+        ["[ ] ", "[x] ", "[X] "]
+            .iter()
+            .find_map(|checkbox| item.strip_prefix(checkbox))
+            .unwrap_or(item),
+    )
+}
+
+/// Check or uncheck a card line, leaving lines without a checkbox alone
+fn set_checked(line: &str, checked: bool) -> String {
+    let mark = if checked { "[x]" } else { "[ ]" };
+    for bullet in ["- ", "* "] {
+        for checkbox in ["[ ]", "[x]", "[X]"] {
+            if let Some(rest) = line.strip_prefix(&format!("{bullet}{checkbox}")) {
+                return format!("{bullet}{mark}{rest}");
+            }
+        }
+    }
+    line.to_string()
+}
+
+/// A Markdown board file, kept line by line
+pub struct MarkdownBoard {
+    path: PathBuf,
+    lines: Vec<String>,
+    crlf: bool,
+}
+
+impl MarkdownBoard {
+    /// Read a board from the contents of its file
+    pub fn parse(path: &Path, text: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            lines: text
+                .split('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+                .collect(),
+            crlf: text.contains("\r\n"),
+        }
+    }
+
+    /// Start a board with the given lanes, laid out the way Obsidian writes it
+    pub fn new(path: &Path, lanes: &[&str]) -> Self {
+        let mut text = String::from("---\n\nkanban-plugin: basic\n\n---\n\n");
+        for (index, lane) in lanes.iter().enumerate() {
+            text.push_str(&format!("## {lane}\n\n"));
+            if index + 1 == lanes.len() {
+                text.push_str(&format!("{COMPLETE_MARKER}\n"));
+            }
+            text.push_str("\n\n");
+        }
+        text.push_str(&format!(
+            "\n{SETTINGS_MARKER}\n```\n{{\"kanban-plugin\":\"basic\"}}\n```\n%%"
+        ));
+        Self::parse(path, &text)
+    }
+
+    /// Get the file contents
+    pub fn render(&self) -> String {
+        self.lines.join(if self.crlf { "\r\n" } else { "\n" })
+    }
+
+    fn line(&self, index: usize) -> &str {
+        self.lines.get(index).map_or("", |l| l.trim_end())
+    }
+
+    fn scan(&self) -> Layout {
+        let mut layout = Layout {
+            title: None,
+            lanes: Vec::new(),
+            cards: Vec::new(),
+        };
+
+        let mut index = 0;
+        if self.line(0) == "---" {
+            index = (1..self.lines.len())
+                .find(|&i| self.line(i) == "---")
+                .map_or(self.lines.len(), |i| i + 1);
+        }
+
+        let mut stop = self.lines.len();
+        while index < self.lines.len() {
+            let line = self.line(index);
+            if line == ARCHIVE_MARKER || line.starts_with(SETTINGS_MARKER) {
+                stop = index;
+                break;
+            }
+
+            if let Some(name) = line.strip_prefix("## ") {
+                if let Some(lane) = layout.lanes.last_mut() {
+                    lane.end = index;
+                }
+                layout.lanes.push(Lane {
+                    name: name.trim().to_string(),
+                    heading: index,
+                    end: self.lines.len(),
+                    complete: false,
+                });
+            } else if let Some(title) = line.strip_prefix("# ") {
+                if layout.lanes.is_empty() {
+                    layout.title.get_or_insert_with(|| title.trim().to_string());
+                }
+            } else if line == COMPLETE_MARKER {
+                if let Some(lane) = layout.lanes.last_mut() {
+                    lane.complete = true;
+                }
+            } else if let (Some(text), false) = (card_text(line), layout.lanes.is_empty()) {
+                let start = index;
+                index += 1;
+                while index < self.lines.len()
+                    && !self.line(index).is_empty()
+                    && self.lines[index].starts_with([' ', '\t'])
+                {
+                    index += 1;
+                }
+                let (text, block) = split_block_id(text);
+                layout.cards.push(Card {
+                    lane: layout.lanes.len() - 1,
+                    start,
+                    end: index,
+                    text: text.to_string(),
+                    block: block.map(String::from),
+                });
+                continue;
+            }
+            index += 1;
+        }
+
+        if let Some(lane) = layout.lanes.last_mut() {
+            lane.end = lane.end.min(stop);
+        }
+        layout
+    }
+
+    /// Get the board title from a `# Title` line above the lanes
+    pub fn title(&self) -> Option<String> {
+        self.scan().title
+    }
+
+    /// Get the lanes as columns, identified by their names
+    pub fn columns(&self) -> Vec<ProjectColumn> {
+        let layout = self.scan();
+        layout
+            .lanes
+            .iter()
+            .enumerate()
+            .map(|(index, lane)| ProjectColumn {
+                id: lane.name.clone(),
+                items_count: layout.cards.iter().filter(|c| c.lane == index).count(),
+                name: lane.name.clone(),
+            })
+            .collect()
+    }
+
+    /// Get the cards as items
+    pub fn items(&self) -> Vec<ProjectItem> {
+        let layout = self.scan();
+        self.items_of(&layout)
+    }
+
+    /// Number cards of the same title in file order, skipping numbers a block ID took
+    fn items_of(&self, layout: &Layout) -> Vec<ProjectItem> {
+        let pinned: HashSet<String> = layout
+            .cards
+            .iter()
+            .filter_map(|card| card.block.as_ref().map(|block| format!("md-{block}")))
+            .collect();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        layout
+            .cards
+            .iter()
+            .map(|card| {
+                let (title, labels, assignees) = parse_card(&card.text);
+                let id = match &card.block {
+                    Some(block) => format!("md-{block}"),
+                    None => {
+                        let occurrence = seen.entry(title.clone()).or_default();
+                        loop {
+                            *occurrence += 1;
+                            let id = card_id(&self.path, &title, *occurrence);
+                            if !pinned.contains(&id) {
+                                break id;
+                            }
+                        }
+                    }
+                };
+                ProjectItem {
+                    id,
+                    title,
+                    assignees,
+                    column_id: layout.lanes[card.lane].name.clone(),
+                    labels,
+                    url: None,
+                    updated_at: None,
+                    content_id: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Find the lines of a card by its item ID
+    fn card_lines(&self, item_id: &str) -> Result<(usize, usize)> {
+        let layout = self.scan();
+        self.items_of(&layout)
+            .iter()
+            .zip(&layout.cards)
+            .find(|(item, _)| item.id == item_id)
+            .map(|(_, card)| (card.start, card.end))
+            .with_context(|| format!("Item {item_id} is not on this board"))
+    }
+
+    /// Pin the IDs of the cards that share a title with `item_id` and are numbered by it
+    ///
+    /// With `from_item` only the cards after it are pinned, as those are the
+    /// ones whose numbers drop when it goes; otherwise only the card itself is.
+    fn pin_ids(&mut self, item_id: &str, from_item: bool) -> Result<()> {
+        let layout = self.scan();
+        let items = self.items_of(&layout);
+        let index = items
+            .iter()
+            .position(|item| item.id == item_id)
+            .with_context(|| format!("Item {item_id} is not on this board"))?;
+        let title = &items[index].title;
+        if items.iter().filter(|item| item.title == *title).count() < 2 {
+            return Ok(());
+        }
+
+        let targets: Vec<usize> = if from_item {
+            (index + 1..items.len()).collect()
+        } else {
+            vec![index]
+        };
+        for target in targets {
+            let card = &layout.cards[target];
+            if items[target].title != *title || card.block.is_some() {
+                continue;
+            }
+            if let Some(block) = items[target].id.strip_prefix("md-") {
+                let line = &mut self.lines[card.start];
+                *line = format!("{} ^{block}", line.trim_end());
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert card lines at the bottom of a lane, checking them when the lane is complete
+    fn insert_card(&mut self, column_id: &str, card: Vec<String>) -> Result<()> {
+        let layout = self.scan();
+        let (index, lane) = layout
+            .lanes
+            .iter()
+            .enumerate()
+            .find(|(_, lane)| lane.name == column_id)
+            .with_context(|| format!("No column {column_id:?} on this board"))?;
+
+        let at = match layout.cards.iter().rfind(|c| c.lane == index) {
+            Some(last) => last.end,
+            None => {
+                // Below the heading's blank line and the complete marker
+                let mut at = lane.heading + 1;
+                if at < lane.end && self.line(at).is_empty() {
+                    at += 1;
+                }
+                if at < lane.end && self.line(at) == COMPLETE_MARKER {
+                    at += 1;
+                }
+                at
+            }
+        };
+
+        let mut card = card;
+        if let Some(first) = card.first_mut() {
+            *first = set_checked(first, lane.complete);
+        }
+        // Keep a blank line between the card and a heading that follows right away
+        if at < self.lines.len() && self.line(at).starts_with('#') {
+            card.push(String::new());
+        }
+        self.lines.splice(at..at, card);
+        Ok(())
+    }
+
+    /// Move a card to the bottom of another lane
+    pub fn move_item(&mut self, item_id: &str, column_id: &str) -> Result<()> {
+        if !self.scan().lanes.iter().any(|l| l.name == column_id) {
+            anyhow::bail!("No column {column_id:?} on this board");
+        }
+        self.pin_ids(item_id, false)?;
+        let (start, end) = self.card_lines(item_id)?;
+        let card: Vec<String> = self.lines.drain(start..end).collect();
+        self.insert_card(column_id, card)
+    }
+
+    /// Add a card to the bottom of a lane
+    pub fn create_item(&mut self, column_id: &str, text: &str) -> Result<ProjectItem> {
+        let (title, _, _) = parse_card(text);
+        let items = self.items();
+        if !items.iter().any(|item| item.title == title) {
+            self.insert_card(column_id, vec![format!("- [ ] {text}")])?;
+            // The new card is the last one with its title, so its ID is found from the back
+            return self
+                .items()
+                .into_iter()
+                .rfind(|item| item.title == title && item.column_id == column_id)
+                .context("Added card not found on the board");
+        }
+
+        // Pin a fresh ID so the card does not take the number of one below it
+        let ids: HashSet<String> = items.into_iter().map(|item| item.id).collect();
+        let id = (1..)
+            .map(|occurrence| card_id(&self.path, &title, occurrence))
+            .find(|id| !ids.contains(id))
+            .context("No free card ID")?;
+        let block = id.trim_start_matches("md-");
+        self.insert_card(
+            column_id,
+            vec![format!("- [ ] {} ^{block}", text.trim_end())],
+        )?;
+        self.items()
+            .into_iter()
+            .find(|item| item.id == id)
+            .context("Added card not found on the board")
+    }
+
+    /// Remove a card and its continuation lines
+    pub fn delete_item(&mut self, item_id: &str) -> Result<()> {
+        self.pin_ids(item_id, true)?;
+        let (start, end) = self.card_lines(item_id)?;
+        self.lines.drain(start..end);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board as Obsidian writes it, with everything a move has to keep
+    const BOARD: &str = "---
+
+kanban-plugin: basic
+
+---
+
+## To do
+
+- [ ] Renew passport #errands @anna
+- [ ] Water plants
+\tthe ones on the balcony too
+- [ ] Water plants
+
+
+## Doing
+
+- [ ] Call the bank @{2024-05-01}
+
+
+## Done
+
+**Complete**
+- [x] Water plants
+
+
+***
+
+## Archive
+
+- [x] Buy milk
+
+%% kanban:settings
+```
+{\"kanban-plugin\":\"basic\"}
+```
+%%
+";
+
+    fn board(text: &str) -> MarkdownBoard {
+        MarkdownBoard::parse(Path::new("/notes/board.md"), text)
+    }
+
+    fn item_id(board: &MarkdownBoard, title: &str, occurrence: usize) -> String {
+        board
+            .items()
+            .into_iter()
+            .filter(|item| item.title == title)
+            .nth(occurrence)
+            .unwrap()
+            .id
+    }
+
+    /// Get the ID and column of every card, sorted by ID
+    fn ids(board: &MarkdownBoard) -> Vec<(String, String)> {
+        let mut ids: Vec<_> = board
+            .items()
+            .into_iter()
+            .map(|item| (item.id, item.column_id))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn reads_lanes_and_cards() {
+        let board = board(BOARD);
+
+        let columns: Vec<(String, usize)> = board
+            .columns()
+            .into_iter()
+            .map(|c| (c.id, c.items_count))
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("To do".to_string(), 3),
+                ("Doing".to_string(), 1),
+                ("Done".to_string(), 1)
+            ]
+        );
+        let first = &board.items()[0];
+        assert_eq!(first.title, "Renew passport");
+        assert_eq!(first.labels, ["errands"]);
+        assert_eq!(first.assignees, ["anna"]);
+        assert_eq!(board.render(), BOARD);
+    }
+
+    #[test]
+    fn moving_a_card_only_touches_its_lines() {
+        let mut board = board(BOARD);
+
+        board
+            .move_item(&item_id(&board, "Renew passport", 0), "Done")
+            .unwrap();
+
+        let expected = BOARD
+            .replace("- [ ] Renew passport #errands @anna\n", "")
+            .replace(
+                "- [x] Water plants\n\n\n***",
+                "- [x] Water plants\n- [x] Renew passport #errands @anna\n\n\n***",
+            );
+        assert_eq!(board.render(), expected);
+    }
+
+    #[test]
+    fn moving_a_card_keeps_its_continuation_lines_and_unchecks_it() {
+        let mut board = board(BOARD);
+        let done = item_id(&board, "Water plants", 2);
+
+        board.move_item(&done, "Doing").unwrap();
+
+        let rendered = board.render();
+        assert!(rendered.contains(&format!(
+            "- [ ] Call the bank @{{2024-05-01}}\n- [ ] Water plants ^{}\n",
+            done.trim_start_matches("md-")
+        )));
+        assert!(rendered.contains("- [ ] Water plants\n\tthe ones on the balcony too\n"));
+        assert!(rendered.contains("**Complete**\n\n\n***"));
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let crlf = BOARD.replace('\n', "\r\n");
+        let mut board = board(&crlf);
+
+        board
+            .move_item(&item_id(&board, "Call the bank @{2024-05-01}", 0), "To do")
+            .unwrap();
+
+        let expected = BOARD
+            .replace("- [ ] Call the bank @{2024-05-01}\n", "")
+            .replace(
+                "- [ ] Water plants\n\n\n## Doing",
+                "- [ ] Water plants\n- [ ] Call the bank @{2024-05-01}\n\n\n## Doing",
+            )
+            .replace('\n', "\r\n");
+        assert_eq!(board.render(), expected);
+    }
+
+    #[test]
+    fn creating_and_deleting_a_card_restores_the_file() {
+        let mut board = board(BOARD);
+
+        let item = board.create_item("Doing", "Book flights #travel").unwrap();
+        assert_eq!(item.title, "Book flights");
+        assert_eq!(item.column_id, "Doing");
+        assert!(board
+            .render()
+            .contains("@{2024-05-01}\n- [ ] Book flights #travel\n"));
+
+        board.delete_item(&item.id).unwrap();
+        assert_eq!(board.render(), BOARD);
+    }
+
+    #[test]
+    fn creating_a_card_in_a_complete_lane_checks_it() {
+        let mut board = board(BOARD);
+
+        board.create_item("Done", "File taxes").unwrap();
+
+        assert!(board
+            .render()
+            .contains("- [x] Water plants\n- [x] File taxes\n\n\n***"));
+    }
+
+    #[test]
+    fn deleting_a_card_takes_its_continuation_lines() {
+        let mut board = board(BOARD);
+
+        board
+            .delete_item(&item_id(&board, "Water plants", 0))
+            .unwrap();
+
+        assert!(!board.render().contains("balcony"));
+        assert_eq!(board.items().len(), 4);
+    }
+
+    #[test]
+    fn archive_cards_are_not_on_the_board() {
+        let board = board(BOARD);
+
+        assert!(board.items().iter().all(|item| item.title != "Buy milk"));
+    }
+
+    #[test]
+    fn card_ids_survive_moving_a_card_of_the_same_title() {
+        let mut board = board(BOARD);
+        let first = item_id(&board, "Water plants", 0);
+        let mut expected = ids(&board);
+
+        board.move_item(&first, "Doing").unwrap();
+
+        for (id, column) in &mut expected {
+            if *id == first {
+                *column = "Doing".to_string();
+            }
+        }
+        assert_eq!(ids(&board), expected);
+    }
+
+    #[test]
+    fn card_ids_survive_deleting_a_card_of_the_same_title() {
+        let mut board = board(BOARD);
+        let first = item_id(&board, "Water plants", 0);
+        let mut before = ids(&board);
+
+        board.delete_item(&first).unwrap();
+
+        before.retain(|(id, _)| *id != first);
+        assert_eq!(ids(&board), before);
+    }
+
+    #[test]
+    fn a_new_card_does_not_take_the_id_of_one_below_it() {
+        let mut board = board(BOARD);
+        let before = ids(&board);
+
+        let item = board.create_item("Doing", "Water plants").unwrap();
+
+        let mut expected = before;
+        expected.push((item.id.clone(), "Doing".to_string()));
+        expected.sort();
+        assert_eq!(ids(&board), expected);
+    }
+
+    #[test]
+    fn block_ids_name_their_cards() {
+        let board = board("## To do\n\n- [ ] Water plants ^plants\n");
+
+        let item = &board.items()[0];
+        assert_eq!(item.id, "md-plants");
+        assert_eq!(item.title, "Water plants");
+    }
+}
@@ -0,0 +1,309 @@
+//! Model Context Protocol server over stdio
+//!
+//! Lets local AI assistants read boards and, when started with writes
+//! allowed, move items and comment on them. Requests and responses are
+//! newline-delimited JSON-RPC 2.0 on stdin and stdout, so nothing else may
+//! print to stdout while the server runs.
+
+use crate::github::{GitHubClient, ProjectData};
+use anyhow::{Context as _, Result};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Protocol version this server implements
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Tools that change data on GitHub, only offered when writes are allowed
+const WRITE_TOOLS: &[&str] = &["move_item", "add_comment"];
+
+/// A JSON-RPC message from the client
+#[derive(Deserialize)]
+struct Message {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// MCP server answering tool calls with a GitHub client
+pub struct Server {
+    client: GitHubClient,
+    allow_writes: bool,
+}
+
+impl Server {
+    /// Create a server, read-only unless `allow_writes` is set
+    pub fn new(client: GitHubClient, allow_writes: bool) -> Self {
+        Self {
+            client,
+            allow_writes,
+        }
+    }
+
+    /// Serve requests from stdin until it is closed
+    pub async fn serve_stdio(&self) -> Result<()> {
+        info!(
+            "MCP server started ({})",
+            if self.allow_writes {
+                "read-write"
+            } else {
+                "read-only"
+            }
+        );
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(response) = self.handle_line(&line).await else {
+                continue;
+            };
+            let mut json = serde_json::to_vec(&response)?;
+            json.push(b'\n');
+            stdout.write_all(&json).await?;
+            stdout.flush().await?;
+        }
+
+        info!("MCP client disconnected");
+        Ok(())
+    }
+
+    /// Answer one line of input, `None` for notifications
+    async fn handle_line(&self, line: &str) -> Option<Value> {
+        let message: Message = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                return Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": -32700, "message": format!("Invalid message: {e}") },
+                }))
+            }
+        };
+        debug!("MCP request: {}", message.method);
+
+        let result = match message.method.as_str() {
+            "initialize" => Ok(self.initialize(&message.params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tools() })),
+            "tools/call" => Ok(self.call_tool(message.params).await),
+            method if method.starts_with("notifications/") => return None,
+            method => {
+                Err(json!({ "code": -32601, "message": format!("Unknown method {method:?}") }))
+            }
+        };
+
+        let id = message.id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let version = params["protocolVersion"]
+            .as_str()
+            .unwrap_or(PROTOCOL_VERSION);
+        if version != PROTOCOL_VERSION {
+            debug!("Client asked for protocol {version}, offering {PROTOCOL_VERSION}");
+        }
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "minik", "version": env!("CARGO_PKG_VERSION") },
+        })
+    }
+
+    /// Describe the tools this server offers
+    fn tools(&self) -> Vec<Value> {
+        let project = json!({
+            "type": "string",
+            "description": "Project as <org>/<number>, or a project node ID",
+        });
+        let item = json!({
+            "type": "string",
+            "description": "Project item ID, issue or PR URL, or #number",
+        });
+
+        let tools = vec![
+            json!({
+                "name": "list_boards",
+                "description": "List the GitHub project boards of the user's organizations",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "org": { "type": "string", "description": "Only list boards of this organization" },
+                    },
+                },
+            }),
+            json!({
+                "name": "read_items",
+                "description": "Read the columns and items of a project board",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": project,
+                        "column": { "type": "string", "description": "Only items in this column" },
+                        "assignee": { "type": "string", "description": "Only items assigned to this login" },
+                    },
+                    "required": ["project"],
+                },
+            }),
+            json!({
+                "name": "move_item",
+                "description": "Move an item to another column of its board",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": project,
+                        "item": item,
+                        "column": { "type": "string", "description": "Column name or ID" },
+                    },
+                    "required": ["project", "item", "column"],
+                },
+            }),
+            json!({
+                "name": "add_comment",
+                "description": "Comment on the issue or pull request behind an item",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": project,
+                        "item": item,
+                        "body": { "type": "string", "description": "Comment in Markdown" },
+                    },
+                    "required": ["project", "item", "body"],
+                },
+            }),
+        ];
+
+        tools
+            .into_iter()
+            .filter(|tool| {
+                self.allow_writes
+                    || !WRITE_TOOLS.contains(&tool["name"].as_str().unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// Run a tool, reporting failures to the model rather than as protocol errors
+    async fn call_tool(&self, params: Value) -> Value {
+        let name = params["name"].as_str().unwrap_or_default().to_string();
+        let arguments = params["arguments"].clone();
+
+        let result = if WRITE_TOOLS.contains(&name.as_str()) && !self.allow_writes {
+            Err(anyhow::anyhow!(
+                "{name} changes GitHub data; start the server with --allow-writes to use it"
+            ))
+        } else {
+            self.run_tool(&name, &arguments).await
+        };
+
+        match result {
+            Ok(value) => json!({
+                "content": [{
+                    "type": "text",
+                    "text": serde_json::to_string_pretty(&value).unwrap_or_default(),
+                }],
+                "isError": false,
+            }),
+            Err(e) => {
+                warn!("MCP tool {name} failed: {e:#}");
+                json!({
+                    "content": [{ "type": "text", "text": format!("{e:#}") }],
+                    "isError": true,
+                })
+            }
+        }
+    }
+
+    async fn run_tool(&self, name: &str, arguments: &Value) -> Result<Value> {
+        let argument = |key: &str| arguments[key].as_str();
+        let required =
+            |key: &str| argument(key).with_context(|| format!("Missing argument {key:?}"));
+
+        match name {
+            "list_boards" => {
+                let mut boards = Vec::new();
+                for org in self.client.list_organizations().await? {
+                    if argument("org")
+                        .is_some_and(|wanted| !wanted.eq_ignore_ascii_case(&org.login))
+                    {
+                        continue;
+                    }
+                    for project in self.client.list_org_projects(&org.login).await? {
+                        boards.push(json!({
+                            "org": org.login,
+                            "number": project.number,
+                            "title": project.title,
+                            "id": project.id,
+                            "url": project.url,
+                        }));
+                    }
+                }
+                Ok(Value::Array(boards))
+            }
+            "read_items" => {
+                let data = self.board(required("project")?).await?;
+                let column = argument("column")
+                    .map(|c| data.find_column(c))
+                    .transpose()?;
+                let assignee = argument("assignee");
+
+                let columns: Vec<Value> = data
+                    .columns
+                    .iter()
+                    .filter(|c| column.is_none_or(|wanted| wanted.id == c.id))
+                    .map(|c| {
+                        let items: Vec<_> = data
+                            .items
+                            .iter()
+                            .filter(|i| i.column_id == c.id)
+                            .filter(|i| assignee.is_none_or(|a| i.assignees.iter().any(|x| x == a)))
+                            .collect();
+                        json!({ "id": c.id, "name": c.name, "items": items })
+                    })
+                    .collect();
+                Ok(json!({ "project": data.project, "columns": columns }))
+            }
+            "move_item" => {
+                let data = self.board(required("project")?).await?;
+                let item = data.find_item(required("item")?)?;
+                let column = data.find_column(required("column")?)?;
+                self.client
+                    .update_item_field(
+                        &data.project.id,
+                        &item.id,
+                        &data.status_field_id,
+                        &column.id,
+                    )
+                    .await?;
+                Ok(json!({ "item": item.id, "title": item.title, "column": column.name }))
+            }
+            "add_comment" => {
+                let data = self.board(required("project")?).await?;
+                let item = data.find_item(required("item")?)?;
+                let content_id = match &item.content_id {
+                    Some(content_id) => content_id.clone(),
+                    None => self.client.item_content_id(&item.id).await?,
+                };
+                let url = self
+                    .client
+                    .add_comment(&content_id, required("body")?)
+                    .await?;
+                Ok(json!({ "item": item.id, "comment_url": url }))
+            }
+            _ => anyhow::bail!("Unknown tool {name:?}"),
+        }
+    }
+
+    async fn board(&self, project: &str) -> Result<ProjectData> {
+        let project_id = self.client.resolve_project(project).await?;
+        self.client.project_data(&project_id).await
+    }
+}
@@ -0,0 +1,279 @@
+//! Durable queue of board mutations made while GitHub was unreachable
+//!
+//! Mutations are stored in `pending_mutations.json` next to `state.json` and
+//! replayed in order once connectivity returns.
+
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A column move that has not reached GitHub yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMutation {
+    /// Unique ID of this queued mutation
+    pub id: String,
+    /// Project the item belongs to
+    pub project_id: String,
+    /// Project item being moved
+    pub item_id: String,
+    /// GraphQL field ID of the status field
+    pub field_id: String,
+    /// Column the user saw the item in when moving it
+    pub from_column_id: Option<String>,
+    /// Column the item should end up in
+    pub to_column_id: String,
+    /// When the move was queued
+    pub queued_at: DateTime<Utc>,
+}
+
+/// Thread-safe queue of pending mutations, persisted on every change
+pub struct MutationQueue(Mutex<Vec<PendingMutation>>);
+
+impl MutationQueue {
+    /// Load the queue left over from a previous session
+    pub fn load() -> Self {
+        Self(Mutex::new(load_pending()))
+    }
+
+    /// Queue a column move, merging it with an earlier pending move of the same item
+    pub fn enqueue(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        field_id: &str,
+        from_column_id: Option<String>,
+        to_column_id: &str,
+    ) -> Result<PendingMutation, String> {
+        let mut pending = self
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to lock mutation queue: {e}"))?;
+
+        let now = Utc::now();
+        let mutation = if let Some(existing) = pending
+            .iter_mut()
+            .find(|m| m.project_id == project_id && m.item_id == item_id)
+        {
+            // Keep the original starting column so conflict detection still works
+            existing.to_column_id = to_column_id.to_string();
+            existing.queued_at = now;
+            existing.clone()
+        } else {
+            let mutation = PendingMutation {
+                id: format!("{}-{item_id}", now.timestamp_millis()),
+                project_id: project_id.to_string(),
+                item_id: item_id.to_string(),
+                field_id: field_id.to_string(),
+                from_column_id,
+                to_column_id: to_column_id.to_string(),
+                queued_at: now,
+            };
+            pending.push(mutation.clone());
+            mutation
+        };
+
+        info!(
+            "Queued move of item {item_id} to column {to_column_id} ({} pending)",
+            pending.len()
+        );
+        save_pending(&pending);
+        Ok(mutation)
+    }
+
+    /// Get the oldest pending mutation that `owned` accepts
+    pub fn front_matching(
+        &self,
+        owned: impl Fn(&PendingMutation) -> bool,
+    ) -> Option<PendingMutation> {
+        self.0.lock().ok()?.iter().find(|m| owned(m)).cloned()
+    }
+
+    /// Remove a mutation once it has been replayed or dropped
+    ///
+    /// A newer move of the item merged into the entry while `sent` was replayed
+    /// stays queued, starting from `column_id`, where the item sits now, if known.
+    pub fn remove(&self, sent: &PendingMutation, column_id: Option<&str>) {
+        match self.0.lock() {
+            Ok(mut pending) => {
+                if let Some(merged) = pending.iter_mut().find(|m| {
+                    m.id == sent.id
+                        && (m.to_column_id != sent.to_column_id || m.queued_at != sent.queued_at)
+                }) {
+                    info!(
+                        "Keeping newer queued move of item {} to column {}",
+                        merged.item_id, merged.to_column_id
+                    );
+                    if let Some(column_id) = column_id {
+                        merged.from_column_id = Some(column_id.to_string());
+                    }
+                } else {
+                    pending.retain(|m| m.id != sent.id);
+                }
+                save_pending(&pending);
+            }
+            Err(e) => error!("Failed to lock mutation queue: {e}"),
+        }
+    }
+
+    /// Get all pending mutations for a project, oldest first
+    pub fn for_project(&self, project_id: &str) -> Vec<PendingMutation> {
+        match self.0.lock() {
+            Ok(pending) => pending
+                .iter()
+                .filter(|m| m.project_id == project_id)
+                .cloned()
+                .collect(),
+            Err(e) => {
+                error!("Failed to lock mutation queue: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get the projects that have moves waiting to be replayed
+    pub fn project_ids(&self) -> BTreeSet<String> {
+        match self.0.lock() {
+            Ok(pending) => pending.iter().map(|m| m.project_id.clone()).collect(),
+            Err(e) => {
+                error!("Failed to lock mutation queue: {e}");
+                BTreeSet::new()
+            }
+        }
+    }
+}
+
+/// Get the path of the queue file
+fn queue_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|p| p.join("pending_mutations.json"))
+}
+
+fn save_pending(pending: &[PendingMutation]) {
+    let Some(path) = queue_path() else {
+        error!("Could not determine config directory");
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            error!("Failed to create config directory: {e}");
+            return;
+        }
+    }
+
+    match serde_json::to_string(pending) {
+        Ok(json) => match crate::state::write_atomic(&path, &json) {
+            Ok(()) => debug!("Saved {} pending mutations to {path:?}", pending.len()),
+            Err(e) => error!("Failed to write mutation queue to {path:?}: {e}"),
+        },
+        Err(e) => error!("Failed to serialize mutation queue: {e}"),
+    }
+}
+
+fn load_pending() -> Vec<PendingMutation> {
+    let Some(path) = queue_path() else {
+        warn!("Could not determine config directory");
+        return Vec::new();
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(json) => match serde_json::from_str::<Vec<PendingMutation>>(&json) {
+            Ok(pending) => {
+                info!("Loaded {} pending mutations from {path:?}", pending.len());
+                pending
+            }
+            Err(e) => {
+                warn!("Failed to parse mutation queue: {e}");
+                Vec::new()
+            }
+        },
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read mutation queue: {e}");
+            }
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{Backends, BoardBackend, Connect};
+    use std::sync::Arc;
+
+    fn queue() -> MutationQueue {
+        crate::config::use_test_dir();
+        MutationQueue(Mutex::new(Vec::new()))
+    }
+
+    fn unreachable() -> Connect {
+        Arc::new(|| -> anyhow::Result<Arc<dyn BoardBackend>> { anyhow::bail!("offline") })
+    }
+
+    #[test]
+    fn merging_a_move_keeps_the_original_column() {
+        let queue = queue();
+        queue
+            .enqueue("PVT_1", "item", "status", Some("todo".to_string()), "doing")
+            .unwrap();
+        let merged = queue
+            .enqueue("PVT_1", "item", "status", Some("doing".to_string()), "done")
+            .unwrap();
+
+        assert_eq!(merged.from_column_id.as_deref(), Some("todo"));
+        assert_eq!(merged.to_column_id, "done");
+        let pending = queue.for_project("PVT_1");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].from_column_id.as_deref(), Some("todo"));
+    }
+
+    #[test]
+    fn removing_a_replayed_move_keeps_a_newer_merged_one() {
+        let queue = queue();
+        let sent = queue
+            .enqueue("PVT_1", "item", "status", Some("todo".to_string()), "doing")
+            .unwrap();
+        queue
+            .enqueue("PVT_1", "item", "status", Some("doing".to_string()), "done")
+            .unwrap();
+
+        queue.remove(&sent, Some("doing"));
+
+        let pending = queue.for_project("PVT_1");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].from_column_id.as_deref(), Some("doing"));
+        assert_eq!(pending[0].to_column_id, "done");
+
+        queue.remove(&pending[0], Some("done"));
+        assert!(queue.for_project("PVT_1").is_empty());
+    }
+
+    #[test]
+    fn replay_only_takes_moves_of_its_backend() {
+        let mut backends = Backends::new(unreachable());
+        backends.register("gitlab", unreachable());
+        let queue = queue();
+        queue
+            .enqueue("PVT_1", "issue", "status", None, "done")
+            .unwrap();
+        queue
+            .enqueue("gitlab:group/1/2", "merge", "status", None, "done")
+            .unwrap();
+        let owned_by = |prefix: Option<&'static str>| {
+            let backends = &backends;
+            move |m: &PendingMutation| backends.prefix_of(&m.project_id) == prefix
+        };
+
+        let gitlab = queue.front_matching(owned_by(Some("gitlab"))).unwrap();
+        assert_eq!(gitlab.item_id, "merge");
+        queue.remove(&gitlab, Some("done"));
+        assert!(queue.front_matching(owned_by(Some("gitlab"))).is_none());
+
+        let github = queue.front_matching(owned_by(None)).unwrap();
+        assert_eq!(github.item_id, "issue");
+        assert_eq!(queue.project_ids().len(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+//! Coordination of in-flight GitHub requests
+//!
+//! Identical concurrent requests share a single call to GitHub, and requests
+//! made for a project can be cancelled once it is no longer selected instead of
+//! retrying in the background for minutes.
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+use log::{debug, error, info};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Error returned by requests that were cancelled before they completed
+pub const CANCELLED: &str = "Request cancelled";
+
+type SharedRequest<T> = Shared<BoxFuture<'static, Result<T, String>>>;
+
+/// Thread-safe map of request key to the request currently running for it
+pub struct InFlight<T>(Mutex<HashMap<String, SharedRequest<T>>>);
+
+impl<T> Default for InFlight<T> {
+    fn default() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> InFlight<T> {
+    /// Run a request, or wait for the identical one already running under `key`
+    ///
+    /// The request stops with [`CANCELLED`] as soon as `token` is cancelled.
+    pub async fn run<F>(&self, key: &str, token: CancellationToken, request: F) -> Result<T, String>
+    where
+        F: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let shared = {
+            let mut requests = self
+                .0
+                .lock()
+                .map_err(|e| format!("Failed to lock in-flight requests: {e}"))?;
+
+            if let Some(existing) = requests.get(key) {
+                debug!("Joining in-flight request {key}");
+                existing.clone()
+            } else {
+                let request = async move {
+                    tokio::select! {
+                        () = token.cancelled() => Err(CANCELLED.to_string()),
+                        result = request => result,
+                    }
+                }
+                .boxed()
+                .shared();
+                requests.insert(key.to_string(), request.clone());
+                request
+            }
+        };
+
+        let result = shared.clone().await;
+
+        // Whoever finishes first clears the entry, unless a newer request took its place
+        if let Ok(mut requests) = self.0.lock() {
+            if requests
+                .get(key)
+                .is_some_and(|current| current.ptr_eq(&shared))
+            {
+                requests.remove(key);
+            }
+        }
+
+        result
+    }
+}
+
+/// Thread-safe map of project ID to the token cancelling its requests
+#[derive(Default)]
+pub struct ProjectRequests(Mutex<HashMap<String, CancellationToken>>);
+
+impl ProjectRequests {
+    /// Get the token for requests made on behalf of a project
+    pub fn token(&self, project_id: &str) -> CancellationToken {
+        match self.0.lock() {
+            Ok(mut tokens) => tokens.entry(project_id.to_string()).or_default().clone(),
+            Err(e) => {
+                error!("Failed to lock project request tokens: {e}");
+                CancellationToken::new()
+            }
+        }
+    }
+
+    /// Cancel every outstanding request made on behalf of a project
+    pub fn cancel(&self, project_id: &str) {
+        match self.0.lock() {
+            Ok(mut tokens) => {
+                if let Some(token) = tokens.remove(project_id) {
+                    info!("Cancelling outstanding requests for project {project_id}");
+                    token.cancel();
+                }
+            }
+            Err(e) => error!("Failed to lock project request tokens: {e}"),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Plumbing shared by the GitLab and Gitea REST clients
+//!
+//! Both read a token from config.toml or the environment, build API URLs below
+//! the instance URL and retry rate limits and server errors the same way.
+
+use crate::github::{self, NetworkError};
+use anyhow::{Context as _, Result};
+use backoff::Error as BackoffError;
+use log::{debug, error, warn};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Get the token from the config, or from `env` when the config has none
+pub fn token(service: &str, configured: Option<&str>, env: &str) -> Result<String> {
+    configured
+        .map(String::from)
+        .or_else(|| std::env::var(env).ok())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .with_context(|| format!("No {service} token in config.toml or {env}"))
+}
+
+/// A request the server answered with an error status
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: StatusCode,
+    message: String,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Check whether an error means the server has nothing at the requested path
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<StatusError>()
+        .is_some_and(|e| e.status == StatusCode::NOT_FOUND)
+}
+
+/// Decide whether a failed response is worth retrying
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// A token-authenticated REST API below an instance URL
+pub struct RestClient {
+    /// Name of the service in logs and errors
+    service: &'static str,
+    api: Url,
+    web: Url,
+    /// Header carrying the token, and its value
+    auth: (&'static str, String),
+    http: reqwest::Client,
+}
+
+impl RestClient {
+    /// Create a client for the API at `api_path` below the instance `url`
+    pub fn new(
+        service: &'static str,
+        url: &str,
+        api_path: &[&str],
+        auth: (&'static str, String),
+    ) -> Result<Self> {
+        let invalid = || format!("Invalid {service} URL {url:?}");
+        let web = Url::parse(url.trim_end_matches('/')).with_context(invalid)?;
+        let mut api = web.clone();
+        api.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!(invalid()))?
+            .pop_if_empty()
+            .extend(api_path);
+
+        Ok(Self {
+            service,
+            api,
+            web,
+            auth,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Build an API URL, escaping every segment so paths with slashes stay one segment
+    pub fn url<S: AsRef<str>>(&self, segments: &[S]) -> Url {
+        let mut url = self.api.clone();
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.extend(segments.iter().map(AsRef::as_ref));
+        }
+        url
+    }
+
+    /// Send a request, retrying rate limits and server errors
+    ///
+    /// Returns the response body and headers, which carry paging details.
+    pub async fn request(
+        &self,
+        method: Method,
+        url: Url,
+        query: &[(&str, String)],
+        body: Option<&Value>,
+    ) -> Result<(Value, HeaderMap)> {
+        let service = self.service;
+        debug!("{service} {method} {url}");
+        let operation = || async {
+            let mut request = self
+                .http
+                .request(method.clone(), url.clone())
+                .header(self.auth.0, &self.auth.1)
+                .header("User-Agent", "Minik-Kanban-App")
+                .query(query)
+                .timeout(Duration::from_secs(30));
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let response = request.send().await.map_err(|e| {
+                warn!("{service} request failed: {e}");
+                BackoffError::transient(
+                    NetworkError(format!("{service} request failed: {e}")).into(),
+                )
+            })?;
+
+            let status = response.status();
+            if is_retryable(status) {
+                let error_body = response.text().await.unwrap_or_default();
+                warn!("{service} returned retryable status {status}: {error_body}");
+                return Err(BackoffError::transient(anyhow::anyhow!(
+                    "Retryable {service} status {status}: {error_body}"
+                )));
+            }
+            if !status.is_success() {
+                let error_body = response.text().await.unwrap_or_default();
+                error!("{service} returned error status {status}: {error_body}");
+                return Err(BackoffError::permanent(
+                    StatusError {
+                        status,
+                        message: format!("{service} request failed with {status}: {error_body}"),
+                    }
+                    .into(),
+                ));
+            }
+
+            let headers = response.headers().clone();
+            let data = response.json::<Value>().await.map_err(|e| {
+                error!("Failed to parse {service} response: {e}");
+                BackoffError::permanent(e.into())
+            })?;
+            Ok((data, headers))
+        };
+
+        backoff::future::retry(github::create_backoff(), operation).await
+    }
+
+    pub async fn get<S: AsRef<str>>(
+        &self,
+        segments: &[S],
+        query: &[(&str, String)],
+    ) -> Result<Value> {
+        let (data, _) = self
+            .request(Method::GET, self.url(segments), query, None)
+            .await?;
+        Ok(data)
+    }
+
+    /// Check whether the instance answers at all
+    pub async fn is_reachable(&self) -> bool {
+        let result = self
+            .http
+            .head(self.web.clone())
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await;
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                debug!("{} is unreachable: {e}", self.service);
+                false
+            }
+        }
+    }
+}
+
+/// Get a header as text, if present and not empty
+pub fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,383 @@
+//! Application state that persists between sessions
+//!
+//! The state lives in `state.json` with a schema version. Older files are
+//! migrated step by step on load, saves are debounced and written atomically,
+//! and the previous good file is kept as `state.json.bak` to fall back on.
+
+use crate::geometry::WindowGeometry;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Current version of the state file schema
+pub const STATE_VERSION: u32 = 2;
+
+/// How long to wait for further changes before writing the state file
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Label of the window declared in `tauri.conf.json`
+pub const MAIN_WINDOW: &str = "main";
+
+/// State of a single board window
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WindowState {
+    pub selected_project_id: Option<String>,
+    pub is_expanded: bool,
+    pub show_only_my_items: bool,
+    pub hidden_columns: Vec<String>,
+    pub window_x: i32,
+    pub window_y: i32,
+    /// Columns of the board last loaded into the window
+    #[serde(skip)]
+    pub column_count: u32,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            selected_project_id: None,
+            is_expanded: false,
+            show_only_my_items: false,
+            hidden_columns: Vec::new(),
+            window_x: 100,
+            window_y: 50,
+            column_count: 5,
+        }
+    }
+}
+
+/// Application state that persists between sessions
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AppState {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub main: WindowState,
+    #[serde(default)]
+    pub windows: BTreeMap<String, WindowState>, // label -> state of additional board windows
+    #[serde(default)]
+    pub project_column_settings: HashMap<String, Vec<String>>, // project_id -> hidden columns
+    #[serde(default)]
+    pub recent_projects: Vec<String>, // most recently selected first
+    #[serde(default)]
+    pub favorite_projects: Vec<String>,
+    #[serde(default)]
+    pub project_geometry: HashMap<String, BTreeMap<String, WindowGeometry>>, // project_id -> display configuration -> geometry
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            main: WindowState::default(),
+            windows: BTreeMap::new(),
+            project_column_settings: HashMap::new(),
+            recent_projects: Vec::new(),
+            favorite_projects: Vec::new(),
+            project_geometry: HashMap::new(),
+        }
+    }
+}
+
+impl AppState {
+    /// Get the state of a window; untracked windows share the main window's state
+    pub fn window(&self, label: &str) -> &WindowState {
+        self.windows.get(label).unwrap_or(&self.main)
+    }
+
+    /// Get the state of a window for changing it
+    pub fn window_mut(&mut self, label: &str) -> &mut WindowState {
+        if label == MAIN_WINDOW {
+            &mut self.main
+        } else {
+            self.windows.entry(label.to_string()).or_default()
+        }
+    }
+
+    /// Iterate over the state of every board window, the main one first
+    pub fn all_windows(&self) -> impl Iterator<Item = (&str, &WindowState)> {
+        std::iter::once((MAIN_WINDOW, &self.main))
+            .chain(self.windows.iter().map(|(label, w)| (label.as_str(), w)))
+    }
+}
+
+/// Thread-safe wrapper for application state
+pub struct AppStateWrapper(pub Mutex<AppState>);
+
+/// Migrations between schema versions; entry `i` upgrades version `i + 1`
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[nest_main_window];
+
+/// Version 2 moves the main window's settings from the top level into `main`
+///
+/// Version 1 files have no `version` field at all.
+fn nest_main_window(state: &mut Map<String, Value>) {
+    let mut main = Map::new();
+    for key in [
+        "selected_project_id",
+        "is_expanded",
+        "show_only_my_items",
+        "hidden_columns",
+        "window_x",
+        "window_y",
+    ] {
+        if let Some(value) = state.remove(key) {
+            main.insert(key.to_string(), value);
+        }
+    }
+    state.insert("main".to_string(), Value::Object(main));
+}
+
+/// Parse a state file, migrating it to the current schema version
+fn parse_state(json: &str) -> Result<AppState, String> {
+    let mut value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid state JSON: {e}"))?;
+    let state = value
+        .as_object_mut()
+        .ok_or_else(|| "State is not a JSON object".to_string())?;
+
+    // No minik wrote version 0; read it like a version 1 file, which has no version
+    let mut version = state
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| u32::try_from(v).unwrap_or(u32::MAX))
+        .max(1);
+    if version > STATE_VERSION {
+        warn!("State file is from a newer minik (version {version}), reading what we can");
+    }
+
+    while version < STATE_VERSION {
+        let migrate = MIGRATIONS
+            .get(version as usize - 1)
+            .ok_or_else(|| format!("No migration from state version {version}"))?;
+        migrate(state);
+        version += 1;
+        info!("Migrated state to version {version}");
+    }
+    state.insert("version".to_string(), Value::from(STATE_VERSION));
+
+    serde_json::from_value(value).map_err(|e| format!("Invalid state: {e}"))
+}
+
+/// Get the path of the state file
+fn state_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|p| p.join("state.json"))
+}
+
+/// Get a path next to the state file with an extra extension
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// State waiting for the debounce timer to write it
+static PENDING_SAVE: Mutex<Option<AppState>> = Mutex::new(None);
+
+/// Save the state, coalescing bursts of changes such as window moves into one write
+pub fn save_state(state: &AppState) {
+    let mut pending = match PENDING_SAVE.lock() {
+        Ok(pending) => pending,
+        Err(e) => {
+            error!("Failed to lock pending state: {e}");
+            return;
+        }
+    };
+
+    let scheduled = pending.is_some();
+    *pending = Some(state.clone());
+    if !scheduled {
+        tauri::async_runtime::spawn(async {
+            tokio::time::sleep(SAVE_DEBOUNCE).await;
+            flush_state();
+        });
+    }
+}
+
+/// Write any state still waiting for the debounce timer right away
+pub fn flush_state() {
+    let pending = match PENDING_SAVE.lock() {
+        Ok(mut pending) => pending.take(),
+        Err(e) => {
+            error!("Failed to lock pending state: {e}");
+            return;
+        }
+    };
+    if let Some(state) = pending {
+        write_state(&state);
+    }
+}
+
+/// Atomically replace the state file, keeping the previous one as a backup
+fn write_state(state: &AppState) {
+    debug!("Saving application state");
+    let Some(path) = state_path() else {
+        error!("Could not determine config directory");
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            error!("Failed to create config directory: {e}");
+            return;
+        }
+    }
+
+    let json = match serde_json::to_string_pretty(state) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize state: {e}");
+            return;
+        }
+    };
+
+    // The current file loaded fine or was written by us, so it is a good backup
+    if path.exists() {
+        if let Err(e) = std::fs::copy(&path, sibling(&path, "bak")) {
+            warn!("Failed to back up state file: {e}");
+        }
+    }
+
+    match write_atomic(&path, &json) {
+        Ok(()) => debug!("State saved successfully to {path:?}"),
+        Err(e) => error!("Failed to write state to {path:?}: {e}"),
+    }
+}
+
+/// Replace a file in one step, so a crash leaves either the old or the new contents
+///
+/// The contents go to `<path>.tmp` first, are synced to disk and then renamed over `path`.
+pub fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp_path = sibling(path, "tmp");
+    let written = std::fs::File::create(&tmp_path).and_then(|mut file| {
+        use std::io::Write as _;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| std::fs::rename(&tmp_path, path)) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Load the state, falling back to the backup and then to defaults
+pub fn load_state() -> AppState {
+    debug!("Loading application state");
+    let Some(path) = state_path() else {
+        warn!("Could not determine config directory");
+        return AppState::default();
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(json) => match parse_state(&json) {
+            Ok(state) => {
+                info!("State loaded successfully from {path:?}");
+                return state;
+            }
+            Err(e) => {
+                warn!("Failed to parse state file: {e}");
+                // Keep the broken file around instead of overwriting it on the next save
+                let broken = sibling(&path, "broken");
+                match std::fs::rename(&path, &broken) {
+                    Ok(()) => warn!("Moved unreadable state file to {broken:?}"),
+                    Err(e) => warn!("Failed to move unreadable state file aside: {e}"),
+                }
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("No existing state file found, using defaults");
+            return AppState::default();
+        }
+        Err(e) => warn!("Failed to read state file: {e}"),
+    }
+
+    let backup = sibling(&path, "bak");
+    match std::fs::read_to_string(&backup).map(|json| parse_state(&json)) {
+        Ok(Ok(state)) => {
+            info!("State restored from backup {backup:?}");
+            return state;
+        }
+        Ok(Err(e)) => warn!("Failed to parse state backup: {e}"),
+        Err(e) => debug!("No usable state backup: {e}"),
+    }
+
+    info!("Using default application state");
+    AppState::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_version_1_into_main_window() {
+        let state = parse_state(
+            &json!({
+                "selected_project_id": "PVT_1",
+                "is_expanded": true,
+                "hidden_columns": ["done"],
+                "window_x": 300,
+                "window_y": 40,
+                "project_column_settings": { "PVT_1": ["done"] },
+                "added_by_a_later_minik": 1
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(state.version, STATE_VERSION);
+        assert_eq!(state.main.selected_project_id.as_deref(), Some("PVT_1"));
+        assert!(state.main.is_expanded);
+        assert_eq!(state.main.hidden_columns, ["done"]);
+        assert_eq!((state.main.window_x, state.main.window_y), (300, 40));
+        // Settings the migration does not touch survive it
+        assert_eq!(state.project_column_settings["PVT_1"], ["done"]);
+    }
+
+    #[test]
+    fn version_0_reads_like_version_1() {
+        let state = parse_state(r#"{"version": 0, "window_x": 7}"#).unwrap();
+        assert_eq!(state.main.window_x, 7);
+    }
+
+    #[test]
+    fn newer_versions_are_read_as_far_as_possible() {
+        let state = parse_state(
+            &json!({ "version": STATE_VERSION + 1, "main": { "window_x": 9 }, "new_field": [] })
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(state.main.window_x, 9);
+        assert_eq!(state.version, STATE_VERSION);
+    }
+
+    #[test]
+    fn rejects_state_that_is_not_an_object() {
+        assert!(parse_state("[1, 2]").is_err());
+        assert!(parse_state("\"state\"").is_err());
+        assert!(parse_state("{").is_err());
+    }
+
+    #[test]
+    fn falls_back_to_the_backup_when_the_state_file_is_broken() {
+        let dir = crate::config::use_test_dir();
+        std::fs::write(dir.join("state.json"), "{ not json").unwrap();
+        std::fs::write(
+            dir.join("state.json.bak"),
+            json!({ "version": STATE_VERSION, "recent_projects": ["PVT_2"] }).to_string(),
+        )
+        .unwrap();
+
+        let state = load_state();
+        assert_eq!(state.recent_projects, ["PVT_2"]);
+        // The broken file is kept for inspection instead of being overwritten
+        assert!(dir.join("state.json.broken").exists());
+    }
+}
@@ -0,0 +1,190 @@
+//! Fuzzy ranking of projects for the quick project switcher
+//!
+//! Favorites come first, then recently used projects, then everything else;
+//! within each group projects are ordered by how well they match the query.
+
+use crate::catalog::Catalog;
+use crate::github::Project;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+/// A project offered by the switcher
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectMatch {
+    /// The matching project
+    pub project: Project,
+    /// Login of the organization owning the project
+    pub org: String,
+    /// How well the project matches the query, higher is better
+    pub score: i64,
+    /// Whether the user starred the project
+    pub favorite: bool,
+    /// Whether the project was used recently
+    pub recent: bool,
+}
+
+/// Score how well `query` matches `candidate` as a case-insensitive subsequence
+///
+/// Consecutive matches and matches at the start of words score higher, and
+/// `None` means some query character could not be matched at all.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for (i, c) in candidate.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if *c != query[matched] {
+            continue;
+        }
+
+        score += 1;
+        if i > 0 && previous_match == Some(i - 1) {
+            score += 5;
+        }
+        if i == 0 || !candidate[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous_match = Some(i);
+        matched += 1;
+    }
+
+    // Among equal matches, prefer shorter names
+    (matched == query.len()).then(|| score * 10 - candidate.len() as i64)
+}
+
+/// Rank the projects of a catalog against a query
+///
+/// `recent` is ordered most recently used first.
+pub fn rank_projects(
+    catalog: &Catalog,
+    query: &str,
+    recent: &[String],
+    favorites: &[String],
+) -> Vec<ProjectMatch> {
+    let mut matches: Vec<ProjectMatch> = catalog
+        .projects
+        .iter()
+        .flat_map(|(org, projects)| projects.iter().map(move |p| (org, p)))
+        .filter_map(|(org, project)| {
+            let score = fuzzy_score(
+                query,
+                &format!("{org}/{} #{}", project.title, project.number),
+            )?;
+            Some(ProjectMatch {
+                project: project.clone(),
+                org: org.clone(),
+                score,
+                favorite: favorites.contains(&project.id),
+                recent: recent.contains(&project.id),
+            })
+        })
+        .collect();
+
+    let recency = |m: &ProjectMatch| {
+        recent
+            .iter()
+            .position(|id| *id == m.project.id)
+            .unwrap_or(usize::MAX)
+    };
+
+    matches.sort_by_key(|m| {
+        let group = if m.favorite {
+            0
+        } else if m.recent {
+            1
+        } else {
+            2
+        };
+        (
+            group,
+            Reverse(m.score),
+            recency(m),
+            m.project.title.to_lowercase(),
+        )
+    });
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::BTreeMap;
+
+    fn project(id: &str, title: &str, number: u64) -> Project {
+        Project {
+            id: id.to_string(),
+            title: title.to_string(),
+            url: format!("https://github.com/orgs/acme/projects/{number}"),
+            number,
+        }
+    }
+
+    fn catalog(projects: Vec<Project>) -> Catalog {
+        Catalog {
+            organizations: Vec::new(),
+            projects: BTreeMap::from([("acme".to_string(), projects)]),
+            fetched_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn characters_out_of_order_or_missing_do_not_match() {
+        assert_eq!(fuzzy_score("xyz", "roadmap"), None);
+        assert_eq!(fuzzy_score("pamdaor", "roadmap"), None);
+        assert!(fuzzy_score("rdm", "Roadmap").is_some());
+        assert_eq!(fuzzy_score(" ", "roadmap"), Some(0));
+    }
+
+    #[test]
+    fn matches_at_word_starts_score_higher() {
+        let start = fuzzy_score("r", "road").unwrap();
+        let middle = fuzzy_score("r", "bark").unwrap();
+        assert!(start > middle, "{start} <= {middle}");
+        assert!(fuzzy_score("pr", "my-proj").unwrap() > fuzzy_score("pr", "mypr-j").unwrap());
+    }
+
+    #[test]
+    fn consecutive_matches_score_higher() {
+        let consecutive = fuzzy_score("ab", "xabx").unwrap();
+        let spread = fuzzy_score("ab", "xaxb").unwrap();
+        assert!(consecutive > spread, "{consecutive} <= {spread}");
+    }
+
+    #[test]
+    fn favorites_come_before_recent_before_other_projects() {
+        let catalog = catalog(vec![
+            project("other", "Roadmap", 1),
+            project("older", "Road trip", 2),
+            project("newer", "Broad plans", 3),
+            project("starred", "Tasks on the road", 4),
+        ]);
+        let recent = ["newer".to_string(), "older".to_string()];
+        let favorites = ["starred".to_string()];
+
+        let ids = |query: &str| -> Vec<String> {
+            rank_projects(&catalog, query, &recent, &favorites)
+                .into_iter()
+                .map(|m| m.project.id)
+                .collect()
+        };
+
+        assert_eq!(ids(""), ["starred", "newer", "older", "other"]);
+        assert_eq!(ids("road"), ["starred", "older", "newer", "other"]);
+        assert_eq!(ids("trip"), ["older"]);
+        assert!(ids("zzz").is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Recording and replaying GitHub traffic
+//!
+//! With `--record <dir>` every GitHub request is sent as usual and the
+//! request and response are written to the directory, one numbered JSON file
+//! per exchange. With `--replay <dir>` nothing goes over the network: each
+//! request is answered with the next recording of the same request, and the
+//! last one again once they run out, so periodic refreshes keep working.
+//! Headers are never recorded, so recordings hold no tokens.
+
+use crate::github::NetworkError;
+use anyhow::{Context as _, Result};
+use log::{debug, info, warn};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// What happens to GitHub traffic
+enum Mode {
+    Record {
+        dir: PathBuf,
+        next: AtomicUsize,
+    },
+    Replay {
+        /// Recordings in order, with whether they were served yet
+        exchanges: Mutex<Vec<(Exchange, bool)>>,
+    },
+}
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+/// A request and the response it got
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    method: String,
+    url: String,
+    /// Request body, JSON when it parses as JSON
+    #[serde(default)]
+    request: Value,
+    status: u16,
+    /// Response body, JSON when it parses as JSON
+    response: Value,
+}
+
+impl Exchange {
+    fn matches(&self, method: &str, url: &str, request: &Value) -> bool {
+        self.method == method && self.url == url && self.request == *request
+    }
+}
+
+fn parse_body(text: &str) -> Value {
+    if text.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+    }
+}
+
+fn render_body(body: &Value) -> String {
+    match body {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        json => json.to_string(),
+    }
+}
+
+/// Get the number of a recording from its file name
+fn recording_number(path: &Path) -> Option<usize> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Get the numbered recordings in a directory, in the order they were made
+fn recordings(dir: &Path) -> Result<Vec<(usize, PathBuf)>> {
+    let mut recordings: Vec<(usize, PathBuf)> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {dir:?}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| Some((recording_number(&path)?, path)))
+        .collect();
+    recordings.sort();
+    Ok(recordings)
+}
+
+/// Write every exchange to `dir`, adding to the recordings already there
+pub fn record(dir: PathBuf) -> Result<()> {
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {dir:?}"))?;
+    // After the highest number, so gaps left by deleted recordings are not overwritten
+    let next = recordings(&dir)?.last().map_or(0, |(number, _)| *number) + 1;
+    info!("Recording GitHub traffic to {dir:?}");
+    set_mode(Mode::Record {
+        dir,
+        next: AtomicUsize::new(next),
+    })
+}
+
+/// Answer every request from the recordings in `dir` instead of the network
+pub fn replay(dir: &Path) -> Result<()> {
+    let mut exchanges = Vec::new();
+    for (_, path) in recordings(dir)? {
+        let text =
+            std::fs::read_to_string(&path).with_context(|| format!("Failed to read {path:?}"))?;
+        let exchange: Exchange =
+            serde_json::from_str(&text).with_context(|| format!("Failed to parse {path:?}"))?;
+        exchanges.push((exchange, false));
+    }
+    info!(
+        "Replaying {} GitHub exchanges from {dir:?}",
+        exchanges.len()
+    );
+    set_mode(Mode::Replay {
+        exchanges: Mutex::new(exchanges),
+    })
+}
+
+fn set_mode(mode: Mode) -> Result<()> {
+    MODE.set(mode)
+        .map_err(|_| anyhow::anyhow!("GitHub traffic is already recorded or replayed"))
+}
+
+/// Check whether requests are answered from recordings rather than the network
+pub fn is_replaying() -> bool {
+    matches!(MODE.get(), Some(Mode::Replay { .. }))
+}
+
+/// Send a request, recording or replaying it as set up, and get its status and body
+///
+/// Failing to reach the server is a [`NetworkError`]; a request that was never
+/// recorded fails with a plain error so it is not retried.
+pub async fn send(request: RequestBuilder) -> Result<(StatusCode, String)> {
+    let (client, request) = request.build_split();
+    let request = request.context("Failed to build request")?;
+    let method = request.method().to_string();
+    let url = request.url().to_string();
+    let body = parse_body(
+        request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .map(String::from_utf8_lossy)
+            .as_deref()
+            .unwrap_or_default(),
+    );
+
+    if let Some(Mode::Replay { exchanges }) = MODE.get() {
+        let mut exchanges = exchanges
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock recordings: {e}"))?;
+        let found = match exchanges
+            .iter()
+            .position(|(e, served)| !served && e.matches(&method, &url, &body))
+        {
+            Some(index) => Some(index),
+            None => exchanges
+                .iter()
+                .rposition(|(e, _)| e.matches(&method, &url, &body)),
+        };
+        let Some(index) = found else {
+            warn!("No recording for {method} {url}");
+            anyhow::bail!("No recording for {method} {url} with this body");
+        };
+        exchanges[index].1 = true;
+        let exchange = &exchanges[index].0;
+        debug!("Replaying {method} {url}");
+        let status = StatusCode::from_u16(exchange.status)
+            .with_context(|| format!("Invalid recorded status {}", exchange.status))?;
+        return Ok((status, render_body(&exchange.response)));
+    }
+
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|e| NetworkError(format!("Request failed: {e}")))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| NetworkError(format!("Failed to read response: {e}")))?;
+
+    if let Some(Mode::Record { dir, next }) = MODE.get() {
+        let exchange = Exchange {
+            method,
+            url,
+            request: body,
+            status: status.as_u16(),
+            response: parse_body(&text),
+        };
+        let path = dir.join(format!("{:05}.json", next.fetch_add(1, Ordering::SeqCst)));
+        match serde_json::to_string_pretty(&exchange) {
+            Ok(json) => match std::fs::write(&path, json) {
+                Ok(()) => debug!("Recorded {} {} to {path:?}", exchange.method, exchange.url),
+                Err(e) => warn!("Failed to write recording {path:?}: {e}"),
+            },
+            Err(e) => warn!("Failed to serialize recording: {e}"),
+        }
+    }
+
+    Ok((status, text))
+}
@@ -0,0 +1,273 @@
+//! `GitHubClient` against a local mock of the GitHub API
+
+use minik_app_lib::github::{Endpoints, GitHubClient};
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const TOKEN: &str = "test-token";
+
+fn client(server: &MockServer) -> GitHubClient {
+    GitHubClient::with_endpoints(TOKEN.to_string(), Endpoints::at(&server.uri()))
+}
+
+fn graphql(data: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "data": data }))
+}
+
+fn item(id: &str, title: &str, option_id: &str) -> Value {
+    json!({
+        "id": id,
+        "updatedAt": "2024-05-01T10:00:00Z",
+        "content": {
+            "id": format!("issue-{id}"),
+            "title": title,
+            "url": format!("https://github.com/org/repo/issues/{id}"),
+            "assignees": { "nodes": [{ "login": "octocat" }] },
+            "labels": { "nodes": [] }
+        },
+        "fieldValues": {
+            "nodes": [{ "field": { "id": "status" }, "optionId": option_id }]
+        }
+    })
+}
+
+fn items_page(nodes: Vec<Value>, end_cursor: Option<&str>) -> ResponseTemplate {
+    graphql(json!({
+        "node": {
+            "items": {
+                "pageInfo": {
+                    "hasNextPage": end_cursor.is_some(),
+                    "endCursor": end_cursor
+                },
+                "nodes": nodes
+            }
+        }
+    }))
+}
+
+#[tokio::test]
+async fn lists_organizations_with_the_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/user/orgs"))
+        .and(header("authorization", format!("Bearer {TOKEN}").as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "id": 1, "login": "acme", "name": "Acme" },
+            { "id": 2, "login": "tools", "name": null }
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let orgs = client(&server).list_organizations().await.unwrap();
+
+    let logins: Vec<&str> = orgs.iter().map(|o| o.login.as_str()).collect();
+    assert_eq!(logins, ["acme", "tools"]);
+}
+
+#[tokio::test]
+async fn follows_item_pages_until_the_last() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .and(body_string_contains("views(first: 1)"))
+        .respond_with(graphql(json!({
+            "node": {
+                "id": "project",
+                "title": "Roadmap",
+                "number": 3,
+                "url": "https://github.com/orgs/org/projects/3",
+                "views": { "nodes": [{ "fields": { "nodes": [{
+                    "id": "status",
+                    "name": "Status",
+                    "options": [
+                        { "id": "todo", "name": "Todo" },
+                        { "id": "done", "name": "Done" }
+                    ]
+                }] } }] }
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .and(body_string_contains("ItemFields"))
+        .and(body_partial_json(json!({ "variables": { "after": null } })))
+        .respond_with(items_page(
+            vec![item("1", "First", "todo"), item("2", "Second", "done")],
+            Some("cursor-1"),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .and(body_string_contains("ItemFields"))
+        .and(body_partial_json(
+            json!({ "variables": { "after": "cursor-1" } }),
+        ))
+        .respond_with(items_page(vec![item("3", "Third", "todo")], None))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = client(&server).project_data("project").await.unwrap();
+
+    let titles: Vec<&str> = data.items.iter().map(|i| i.title.as_str()).collect();
+    assert_eq!(titles, ["First", "Second", "Third"]);
+    assert_eq!(data.status_field_id, "status");
+    let counts: Vec<(&str, usize)> = data
+        .columns
+        .iter()
+        .map(|c| (c.id.as_str(), c.items_count))
+        .collect();
+    assert_eq!(counts, [("todo", 2), ("done", 1)]);
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/user/orgs"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/user/orgs"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!([{ "id": 1, "login": "acme" }])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let orgs = client(&server).list_organizations().await.unwrap();
+
+    assert_eq!(orgs.len(), 1);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/user/orgs"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Bad credentials"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = client(&server).list_organizations().await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn retries_transient_graphql_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(graphql(json!({
+            "organization": { "projectsV2": { "nodes": [{
+                "id": "project",
+                "title": "Roadmap",
+                "number": 3,
+                "url": "https://github.com/orgs/acme/projects/3"
+            }] } }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let projects = client(&server).list_org_projects("acme").await.unwrap();
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].title, "Roadmap");
+}
+
+#[tokio::test]
+async fn fails_on_other_graphql_errors_without_retrying() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "errors": [{
+                "type": "NOT_FOUND",
+                "message": "Could not resolve to an Organization with the login of 'nobody'."
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = client(&server)
+        .list_org_projects("nobody")
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("Could not resolve"), "{error}");
+    assert!(!minik_app_lib::github::is_network_error(&error));
+}
+
+#[tokio::test]
+async fn moves_an_item_with_update_item_field() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .and(header("authorization", format!("Bearer {TOKEN}").as_str()))
+        .and(body_string_contains("updateProjectV2ItemFieldValue"))
+        .and(body_partial_json(json!({
+            "variables": {
+                "projectId": "project",
+                "itemId": "item",
+                "fieldId": "status",
+                "value": { "singleSelectOptionId": "done" }
+            }
+        })))
+        .respond_with(graphql(json!({
+            "updateProjectV2ItemFieldValue": {
+                "projectV2Item": { "id": "item", "updatedAt": "2024-05-02T08:30:00Z" }
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let updated_at = client(&server)
+        .update_item_field("project", "item", "status", "done")
+        .await
+        .unwrap();
+
+    assert_eq!(updated_at.as_deref(), Some("2024-05-02T08:30:00Z"));
+}
+
+#[tokio::test]
+async fn fails_a_move_without_a_payload() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(graphql(json!({
+            "updateProjectV2ItemFieldValue": { "projectV2Item": null }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .update_item_field("project", "item", "status", "done")
+        .await;
+
+    assert!(result.is_err());
+}
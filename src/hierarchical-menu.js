@@ -190,28 +190,13 @@ async function showHierarchicalContextMenu(mouseX, mouseY) {
         if (window.cachedProjectData) {
             populateProjectsSubmenu(submenu, window.cachedProjectData);
         } else {
-            // Fetch projects
+            // Served from the backend's catalog, which refreshes itself when it gets old
             try {
-                const orgs = await invoke('list_organizations');
-                const projectsByOrg = {};
-
-                // Fetch all projects in parallel
-                const promises = orgs.map(async (org) => {
-                    try {
-                        const projects = await invoke('list_org_projects', { org: org.login });
-                        if (projects && projects.length > 0) {
-                            projectsByOrg[org.login] = projects;
-                        }
-                    } catch (error) {
-                        console.error(`Failed to fetch projects for ${org.login}:`, error);
-                    }
-                });
-
-                await Promise.all(promises);
+                const projectsByOrg = await invoke('project_catalog');
                 window.cachedProjectData = projectsByOrg;
                 populateProjectsSubmenu(submenu, projectsByOrg);
             } catch (error) {
-                console.error('Failed to fetch organizations:', error);
+                console.error('Failed to load project catalog:', error);
                 submenu.innerHTML = '<div class="context-menu-error">Failed to load projects</div>';
             }
         }
@@ -284,6 +269,33 @@ async function showHierarchicalContextMenu(mouseX, mouseY) {
         content.appendChild(columnsItem);
     }
 
+    // Window management: open another board window, close this one unless it is the main window
+    const newWindowItem = document.createElement('div');
+    newWindowItem.className = 'context-menu-item';
+    newWindowItem.textContent = 'New Window';
+    newWindowItem.addEventListener('click', async () => {
+        const currentData = window.getCurrentProjectData ? window.getCurrentProjectData() : null;
+        if (window.removeContextMenu) await window.removeContextMenu();
+        try {
+            await invoke('open_project_window', {
+                projectId: currentData ? currentData.project.id : null
+            });
+        } catch (error) {
+            console.error('Failed to open window:', error);
+        }
+    });
+    content.appendChild(newWindowItem);
+
+    const { getCurrentWindow } = window.__TAURI__.window;
+    if (getCurrentWindow().label !== 'main') {
+        const closeWindowItem = document.createElement('div');
+        closeWindowItem.className = 'context-menu-item';
+        closeWindowItem.textContent = 'Close Window';
+        closeWindowItem.addEventListener('click', async () => {
+            await invoke('close_window');
+        });
+        content.appendChild(closeWindowItem);
+    }
 
     // Append menu to body
     document.body.appendChild(menu);
@@ -386,6 +398,7 @@ function populateProjectsSubmenu(submenu, projectsByOrg) {
 
     if (!hasProjects) {
         submenu.innerHTML = '<div class="context-menu-no-items">No projects found</div>';
+        appendRefreshProjectsItem(submenu);
         return;
     }
 
@@ -433,6 +446,29 @@ function populateProjectsSubmenu(submenu, projectsByOrg) {
             submenu.appendChild(projectItem);
         });
     }
+
+    appendRefreshProjectsItem(submenu);
+}
+
+// Add the action that fetches the project list from GitHub again
+function appendRefreshProjectsItem(submenu) {
+    const refreshItem = document.createElement('div');
+    refreshItem.className = 'context-menu-item context-menu-refresh';
+    refreshItem.textContent = '↻ Refresh project list';
+    refreshItem.addEventListener('click', async (e) => {
+        e.stopPropagation();
+        submenu.innerHTML = '<div class="context-menu-loading">Refreshing...</div>';
+        try {
+            const projectsByOrg = await invoke('refresh_project_list');
+            window.cachedProjectData = projectsByOrg;
+            populateProjectsSubmenu(submenu, projectsByOrg);
+        } catch (error) {
+            console.error('Failed to refresh project list:', error);
+            submenu.innerHTML = '<div class="context-menu-error">Failed to refresh projects</div>';
+            appendRefreshProjectsItem(submenu);
+        }
+    });
+    submenu.appendChild(refreshItem);
 }
 
 // Populate columns submenu
@@ -1,6 +1,10 @@
 // Wait for Tauri to be available
 const { invoke } = window.__TAURI__.core;
 
+// Listen on this window only, so events meant for other board windows are ignored
+const currentWindow = window.__TAURI__.webviewWindow.getCurrentWebviewWindow();
+const listen = currentWindow.listen.bind(currentWindow);
+
 // Check if shell plugin is available
 let open;
 if (window.__TAURI__.shell) {
@@ -12,7 +16,9 @@ if (window.__TAURI__.shell) {
 
 let currentProjectData = null;
 let isExpanded = false;
-let refreshInterval = null;
+let recentlyChangedItems = new Set(); // item IDs to highlight after a background refresh
+let renderDeferred = false; // a background change arrived while dragging
+let requestedProjectId = null; // project whose data was asked for last
 let draggedItem = null;
 let isDragging = false;
 let dragElement = null;
@@ -23,7 +29,8 @@ let showOnlyMyItems = false;
 let currentColumns = [];
 let availableProjects = {}; // org -> projects map
 
-const COLUMN_COLORS = ['yellow', 'blue', 'green', 'pink', 'orange', 'purple'];
+let COLUMN_COLORS = ['yellow', 'blue', 'green', 'pink', 'orange', 'purple'];
+let boardLayout = { column_width: 190, column_gap: 4, padding: 12, card_density: 'comfortable' }; // from config.toml
 
 // Initialize app
 document.addEventListener('DOMContentLoaded', async () => {
@@ -32,6 +39,12 @@ document.addEventListener('DOMContentLoaded', async () => {
     // Setup window dragging for frameless window
     setupWindowDragging();
 
+    try {
+        applyConfig(await invoke('app_config'));
+    } catch (error) {
+        console.warn('Failed to load config, using defaults:', error);
+    }
+
     try {
         updateStatus('Checking GitHub authentication...');
         await checkAuth();
@@ -55,7 +68,7 @@ document.addEventListener('DOMContentLoaded', async () => {
 
         updateStatus('Setting up interface...');
         setupEventListeners();
-        startAutoRefresh();
+        setupBoardListeners();
 
         // If no project is loaded, try to load the first available project
         if (!currentProjectData) {
@@ -69,6 +82,14 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
 });
 
+// Apply the settings from config.toml
+function applyConfig(config) {
+    COLUMN_COLORS = config.colors.columns;
+    window.COLUMN_COLORS = COLUMN_COLORS;
+    boardLayout = config.layout;
+    document.body.classList.toggle('density-compact', boardLayout.card_density === 'compact');
+}
+
 async function checkAuth() {
     try {
         await invoke('github_token');
@@ -99,6 +120,7 @@ async function loadSavedProject() {
     try {
         const projectId = await invoke('current_project');
         if (projectId) {
+            await showCachedProjectData(projectId);
             updateStatus('Loading saved project data...');
             await loadProjectData(projectId);
         } else {
@@ -165,12 +187,40 @@ async function loadFirstAvailableProject() {
     }
 }
 
+// Render the last cached board right away, before GitHub has answered
+async function showCachedProjectData(projectId) {
+    try {
+        const cached = await invoke('cached_project_data', { projectId });
+        if (cached) {
+            console.log('Showing cached board fetched at', cached.fetched_at);
+            currentProjectData = cached;
+            currentProjectData.hiddenColumns = cached.hidden_columns || [];
+            renderProject();
+        }
+    } catch (error) {
+        console.warn('Failed to load cached board:', error);
+    }
+}
+
 async function loadProjectData(projectId) {
+    requestedProjectId = projectId;
     try {
         updateStatus('Fetching project data from GitHub...');
         const projectData = await invoke('project_data', { projectId });
+        if (projectId !== requestedProjectId) {
+            console.log('Ignoring data for project that is no longer selected:', projectId);
+            return;
+        }
         currentProjectData = projectData;
 
+        // Show moves that have not reached GitHub yet in their target column
+        try {
+            const pending = await invoke('pending_mutations', { projectId });
+            applyPendingMutations(pending);
+        } catch (error) {
+            console.warn('Failed to load pending mutations:', error);
+        }
+
         // Load saved column visibility settings for this project
         try {
             const hiddenColumns = await invoke('hidden_columns');
@@ -192,16 +242,32 @@ async function loadProjectData(projectId) {
         updateStatus('Rendering project...');
         renderProject();
     } catch (error) {
+        // Another project was selected while this one was loading
+        if (projectId !== requestedProjectId || error === 'Request cancelled') {
+            console.log('Loading project was superseded:', projectId);
+            return;
+        }
         updateStatus('Failed to load project data');
         showError(`Failed to load project: ${error}`);
     }
 }
 
+function applyPendingMutations(pending) {
+    for (const mutation of pending) {
+        const item = currentProjectData.items.find(i => i.id === mutation.item_id);
+        if (item) {
+            item.column_id = mutation.to_column_id;
+            item.pending = true;
+        }
+    }
+}
+
 function renderProject() {
     if (!currentProjectData) return;
 
     renderMinimizedView();
     renderExpandedView();
+    renderStaleIndicator();
 
     // Set proper window size based on current view
     if (isExpanded) {
@@ -303,8 +369,8 @@ function renderMinimizedView() {
 
         return `
             <span class="column-badge ${colorClass}">
-                <span class="column-name">${initials}•${itemCount}</span>
-                <span class="column-items">${itemPreview}</span>
+                <span class="column-name">${escapeHtml(initials)}•${itemCount}</span>
+                <span class="column-items">${escapeHtml(itemPreview)}</span>
             </span>
         `;
     }).join('');
@@ -312,6 +378,24 @@ function renderMinimizedView() {
     summary.innerHTML = columnsHtml || '<span class="loading">No project selected</span>';
 }
 
+// Mark boards served from the offline cache with the time they were fetched
+function renderStaleIndicator() {
+    const indicator = document.getElementById('stale-indicator');
+    if (!indicator) return;
+
+    if (currentProjectData && currentProjectData.stale) {
+        const fetchedAt = currentProjectData.fetched_at ? new Date(currentProjectData.fetched_at) : null;
+        const when = fetchedAt
+            ? fetchedAt.toLocaleString([], { month: 'short', day: 'numeric', hour: '2-digit', minute: '2-digit' })
+            : 'unknown time';
+        indicator.textContent = `offline · ${when}`;
+        indicator.title = `Showing cached board from ${when}`;
+        indicator.classList.remove('hidden');
+    } else {
+        indicator.classList.add('hidden');
+    }
+}
+
 async function calculateAndSetWindowHeight() {
     console.log('calculateAndSetWindowHeight called');
     // Wait for render to complete
@@ -339,9 +423,9 @@ async function calculateAndSetWindowHeight() {
             totalWidth += width;
         });
 
-        // Add padding from the board (6px left + 6px right = 12px) and gaps between columns
-        const gaps = Math.max(0, columns.length - 1) * 4; // 4px gap between columns
-        const totalWidthWithPadding = Math.ceil(totalWidth + 12 + gaps);
+        // Add padding from the board and gaps between columns
+        const gaps = Math.max(0, columns.length - 1) * boardLayout.column_gap;
+        const totalWidthWithPadding = Math.ceil(totalWidth + boardLayout.padding + gaps);
 
         // Add padding from the board (6px top + 6px bottom = 12px)
         const totalHeight = Math.ceil(maxHeight + 12);
@@ -385,6 +469,9 @@ function renderExpandedView() {
         return;
     }
 
+    // Cards on local boards can be added and deleted from the window
+    const editable = isLocalBoard(currentProjectData);
+
     const columnsHtml = visibleColumns.map((column, index) => {
         const colorClass = `column-${COLUMN_COLORS[index % COLUMN_COLORS.length]}`;
         let items = currentProjectData.items.filter(item => item.column_id === column.id);
@@ -399,12 +486,13 @@ function renderExpandedView() {
         const cardsHtml = items.map(item => {
             const hasMetadata = item.assignees.length > 0 || item.labels.length > 0;
             return `
-                <div class="kanban-card"
+                <div class="kanban-card${item.pending ? ' pending' : ''}${recentlyChangedItems.has(item.id) ? ' recently-changed' : ''}"
                      draggable="true"
-                     data-item-id="${item.id}"
-                     data-column-id="${column.id}"
-                     data-url="${item.url || '#'}">
+                     data-item-id="${escapeHtml(item.id)}"
+                     data-column-id="${escapeHtml(column.id)}"
+                     data-url="${escapeHtml(item.url || '#')}">
                     <div class="card-title">${escapeHtml(item.title)}</div>
+                    ${editable ? '<button class="card-delete" title="Delete card">×</button>' : ''}
                     ${hasMetadata ? `
                         <div class="card-meta">
                             ${item.assignees.length > 0 ?
@@ -421,9 +509,10 @@ function renderExpandedView() {
         }).join('');
 
         return `
-            <div class="kanban-column ${colorClass}" data-column-id="${column.id}">
+            <div class="kanban-column ${colorClass}" data-column-id="${escapeHtml(column.id)}">
                 <div class="column-header">
                     <span>${escapeHtml(column.name)}</span>
+                    ${editable ? '<button class="column-add" title="Add card">+</button>' : ''}
                     <span class="column-count-badge">${items.length}</span>
                 </div>
                 <div class="column-cards">
@@ -435,6 +524,10 @@ function renderExpandedView() {
 
     board.innerHTML = columnsHtml || '<div style="padding: 20px; color: #999;">No columns to display</div>';
 
+    if (editable) {
+        setupLocalCardEditing(board);
+    }
+
     // Add click and custom drag handlers for cards
     const cards = board.querySelectorAll('.kanban-card');
     console.log(`Setting up drag handlers for ${cards.length} cards`);
@@ -575,40 +668,10 @@ function renderExpandedView() {
                 // Only update if moved to a different column
                 if (itemToMove.fromColumnId !== toColumnId) {
                     console.log(`🚀 Moving item ${itemToMove.itemId} to ${toColumnName}`);
-
-                    try {
-                        console.log('📡 Calling update_item_column with:', {
-                            projectId: currentProjectData.project.id,
-                            itemId: itemToMove.itemId,
-                            columnId: toColumnId
-                        });
-
-                        await invoke('update_item_column', {
-                            projectId: currentProjectData.project.id,
-                            itemId: itemToMove.itemId,
-                            columnId: toColumnId
-                        });
-
-                        console.log('✅ GitHub update successful');
-
-                        // Update local data
-                        const item = currentProjectData.items.find(i => i.id === itemToMove.itemId);
-                        if (item) {
-                            console.log('📝 Updating local item data');
-                            const oldColumnId = item.column_id;
-                            item.column_id = toColumnId;
-                            console.log(`Local update: ${oldColumnId} -> ${toColumnId}`);
-                            renderExpandedView();
-                            renderMinimizedView();
-                        } else {
-                            console.error('❌ Item not found in local data:', itemToMove.itemId);
-                        }
-                    } catch (error) {
-                        console.error('❌ Failed to update item:', error);
-                        showError(`Failed to move item: ${error}`);
-                        // Refresh to restore correct state
-                        await loadProjectData(currentProjectData.project.id);
-                    }
+                    // Reset drag state before awaiting so other handlers don't replay the drop
+                    isDragging = false;
+                    draggedItem = null;
+                    await moveItemOptimistically(itemToMove.itemId, itemToMove.fromColumnId, toColumnId);
                 } else {
                     console.log('ℹ️ Item dropped in same column, no update needed');
                 }
@@ -618,6 +681,12 @@ function renderExpandedView() {
             isDragging = false;
             draggedItem = null;
 
+            // Apply background changes that arrived during the drag
+            if (renderDeferred) {
+                renderDeferred = false;
+                renderProject();
+            }
+
             // Small delay to prevent click event from firing
             setTimeout(() => {
                 isDragging = false;
@@ -626,6 +695,61 @@ function renderExpandedView() {
     });
 }
 
+// Move a card locally right away, then confirm with GitHub and undo exactly that move if needed
+async function moveItemOptimistically(itemId, fromColumnId, toColumnId) {
+    const item = currentProjectData.items.find(i => i.id === itemId);
+    if (!item) {
+        console.error('❌ Item not found in local data:', itemId);
+        return;
+    }
+
+    const projectId = currentProjectData.project.id;
+    item.column_id = toColumnId;
+    renderExpandedView();
+    renderMinimizedView();
+
+    const columnName = (columnId) => {
+        const column = currentProjectData.columns.find(c => c.id === columnId);
+        return column ? column.name : 'another column';
+    };
+
+    try {
+        console.log('📡 Calling update_item_column with:', { projectId, itemId, fromColumnId, columnId: toColumnId });
+        const outcome = await invoke('update_item_column', {
+            projectId,
+            itemId,
+            columnId: toColumnId,
+            fromColumnId
+        });
+
+        // The user may have switched projects while we were waiting
+        if (!currentProjectData || currentProjectData.project.id !== projectId) return;
+
+        switch (outcome.status) {
+            case 'applied':
+                console.log('✅ GitHub update successful');
+                item.pending = false;
+                break;
+            case 'queued':
+                console.log('📴 GitHub unreachable, move queued for replay');
+                item.pending = true;
+                break;
+            case 'conflict':
+                console.warn('⚠️ Item was moved by someone else to', outcome.current_column_id);
+                item.column_id = outcome.current_column_id || fromColumnId;
+                showError(`Someone else moved "${item.title}" to ${columnName(item.column_id)} - your move was not applied`);
+                break;
+        }
+    } catch (error) {
+        console.error('❌ Failed to update item, rolling back:', error);
+        item.column_id = fromColumnId;
+        showError(`Failed to move item: ${error}`);
+    }
+
+    renderExpandedView();
+    renderMinimizedView();
+}
+
 function setupEventListeners() {
     // Note: Double-click handling is now managed by the window dragging system
 
@@ -670,6 +794,24 @@ function setupEventListeners() {
     // Users can double-click or press ESC to minimize instead
 }
 
+// Cmd+Z / Shift+Cmd+Z come from the Edit menu: inside text fields they undo
+// typing, everywhere else they undo and redo board changes
+function undoOrRedo(command) {
+    if (isEditingText(document.activeElement)) {
+        document.execCommand(command);
+        return;
+    }
+    invoke(command).catch((error) => {
+        console.error('Undo/redo failed:', error);
+        showError(`Undo/redo failed: ${error}`);
+    });
+}
+
+function isEditingText(target) {
+    return target instanceof HTMLElement &&
+        (target.isContentEditable || ['INPUT', 'TEXTAREA', 'SELECT'].includes(target.tagName));
+}
+
 async function toggleView() {
     isExpanded = await invoke('toggle_expanded');
     const dragHandle = document.getElementById('window-drag-handle');
@@ -740,13 +882,163 @@ async function setMinimizedViewSize() {
     }
 }
 
-function startAutoRefresh() {
-    // Refresh every 90 seconds
-    refreshInterval = setInterval(async () => {
-        if (currentProjectData) {
-            await loadProjectData(currentProjectData.project.id);
+// The backend polls GitHub and tells us exactly which items changed
+function setupBoardListeners() {
+    const isCurrentProject = (projectId) =>
+        currentProjectData && currentProjectData.project.id === projectId;
+
+    listen('item-added', (event) => {
+        const { project_id, item } = event.payload;
+        if (!isCurrentProject(project_id)) return;
+        console.log('Item added:', item.id);
+        currentProjectData.items.push(item);
+        highlightChangedItem(item.id);
+    });
+
+    listen('item-moved', (event) => {
+        const { project_id, item } = event.payload;
+        if (!isCurrentProject(project_id)) return;
+        console.log('Item moved:', item.id, '->', item.column_id);
+        replaceItem(item);
+        highlightChangedItem(item.id);
+    });
+
+    listen('item-updated', (event) => {
+        const { project_id, item } = event.payload;
+        if (!isCurrentProject(project_id)) return;
+        console.log('Item updated:', item.id);
+        replaceItem(item);
+        highlightChangedItem(item.id);
+    });
+
+    listen('item-removed', (event) => {
+        const { project_id, item_id } = event.payload;
+        if (!isCurrentProject(project_id)) return;
+        console.log('Item removed:', item_id);
+        currentProjectData.items = currentProjectData.items.filter(i => i.id !== item_id);
+        scheduleRender();
+    });
+
+    listen('board-replaced', (event) => {
+        const data = event.payload;
+        if (!isCurrentProject(data.project.id)) return;
+        console.log('Board replaced by backend refresh');
+        currentProjectData = data;
+        currentProjectData.hiddenColumns = data.hidden_columns || [];
+        scheduleRender();
+    });
+
+    listen('board-refreshed', (event) => {
+        const [projectId, fetchedAt] = event.payload;
+        if (!isCurrentProject(projectId)) return;
+        currentProjectData.stale = false;
+        currentProjectData.fetched_at = fetchedAt;
+        renderStaleIndicator();
+    });
+
+    listen('board-stale', (event) => {
+        if (!isCurrentProject(event.payload)) return;
+        console.warn('Background refresh failed, board is stale');
+        currentProjectData.stale = true;
+        renderStaleIndicator();
+    });
+}
+
+// Swap in the backend's version of an item, keeping moves that are still queued locally
+function replaceItem(updated) {
+    const index = currentProjectData.items.findIndex(i => i.id === updated.id);
+    if (index === -1) {
+        currentProjectData.items.push(updated);
+        return;
+    }
+    const existing = currentProjectData.items[index];
+    if (existing.pending) {
+        updated.column_id = existing.column_id;
+        updated.pending = true;
+    }
+    currentProjectData.items[index] = updated;
+}
+
+function highlightChangedItem(itemId) {
+    recentlyChangedItems.add(itemId);
+    scheduleRender();
+    setTimeout(() => {
+        recentlyChangedItems.delete(itemId);
+        const card = document.querySelector(`.kanban-card[data-item-id="${CSS.escape(itemId)}"]`);
+        if (card) {
+            card.classList.remove('recently-changed');
         }
-    }, 90 * 1000);
+    }, 4000);
+}
+
+function isLocalBoard(data) {
+    return data && data.project.id.startsWith('local:');
+}
+
+// Add and delete buttons of local boards; the backend announces the result with item events
+function setupLocalCardEditing(board) {
+    const projectId = currentProjectData.project.id;
+
+    board.querySelectorAll('.column-add').forEach(button => {
+        // Keep the header from starting a window drag
+        button.addEventListener('mousedown', (e) => e.stopPropagation());
+        button.addEventListener('click', (e) => {
+            e.stopPropagation();
+            const column = button.closest('.kanban-column');
+            const cards = column.querySelector('.column-cards');
+            if (cards.querySelector('.card-input')) return;
+
+            const input = document.createElement('input');
+            input.className = 'card-input';
+            input.placeholder = 'New card';
+            cards.prepend(input);
+            input.focus();
+
+            input.addEventListener('keydown', async (e) => {
+                if (e.key === 'Escape') {
+                    input.remove();
+                } else if (e.key === 'Enter' && input.value.trim()) {
+                    const title = input.value.trim();
+                    input.disabled = true;
+                    try {
+                        await invoke('create_item', { projectId, columnId: column.dataset.columnId, title });
+                        input.remove();
+                    } catch (error) {
+                        console.error('Failed to add card:', error);
+                        showError(`Failed to add card: ${error}`);
+                        input.disabled = false;
+                    }
+                }
+            });
+            input.addEventListener('blur', () => {
+                if (!input.disabled) input.remove();
+            });
+        });
+    });
+
+    board.querySelectorAll('.card-delete').forEach(button => {
+        // Keep the card from starting a drag
+        button.addEventListener('mousedown', (e) => e.stopPropagation());
+        button.addEventListener('click', async (e) => {
+            e.stopPropagation();
+            const itemId = button.closest('.kanban-card').dataset.itemId;
+            try {
+                await invoke('delete_item', { projectId, itemId });
+            } catch (error) {
+                console.error('Failed to delete card:', error);
+                showError(`Failed to delete card: ${error}`);
+            }
+        });
+    });
+}
+
+// Re-render after a background change, unless the user is in the middle of a drag
+function scheduleRender() {
+    if (isDragging) {
+        renderDeferred = true;
+        return;
+    }
+    renderProject();
 }
 
 function showError(message) {
@@ -788,7 +1080,6 @@ function setupMenuListeners() {
         return;
     }
 
-    const { listen } = window.__TAURI__.event;
 
     // Listen for menu refresh event
     listen('menu-refresh', async () => {
@@ -798,12 +1089,60 @@ function setupMenuListeners() {
         }
     });
 
+    // Refresh as soon as the board's backend is reachable again after serving a cached board
+    listen('connectivity-restored', async (event) => {
+        console.log('Connectivity restored for', event.payload);
+        if (currentProjectData && event.payload.includes(currentProjectData.project.id)) {
+            await refreshProject();
+        }
+    });
+
+    // Queued moves that could not be replayed need the real board state
+    listen('mutation-conflict', async (event) => {
+        const [mutation] = event.payload;
+        console.warn('Queued move conflicted with a change on GitHub:', event.payload);
+        showError('A card you moved offline was changed on GitHub in the meantime - your move was discarded');
+        if (currentProjectData && currentProjectData.project.id === mutation.project_id) {
+            await refreshProject();
+        }
+    });
+
+    listen('mutation-failed', async (event) => {
+        const [mutation, error] = event.payload;
+        console.error('Queued move failed:', event.payload);
+        showError(`Failed to apply offline move: ${error}`);
+        if (currentProjectData && currentProjectData.project.id === mutation.project_id) {
+            await refreshProject();
+        }
+    });
+
+    listen('menu-undo', () => undoOrRedo('undo'));
+    listen('menu-redo', () => undoOrRedo('redo'));
+
+    // Undo/redo changed the board on GitHub, reload it to show the result
+    listen('history-applied', async (event) => {
+        console.log('Undo/redo applied:', event.payload);
+        if (currentProjectData && currentProjectData.project.id === event.payload.project_id) {
+            await refreshProject();
+        }
+    });
+
+    listen('history-failed', (event) => {
+        console.error('Undo/redo failed:', event.payload);
+        showError(`Undo/redo failed: ${event.payload}`);
+    });
+
     // Listen for menu toggle my items event
     listen('menu-toggle-my-items', async () => {
         console.log('Menu toggle my items triggered');
         await toggleMyItems();
     });
 
+    // The filter changed, from the menu or a minik:// link
+    listen('my-items-changed', async (event) => {
+        await applyMyItemsFilter(event.payload);
+    });
+
     // Listen for menu toggle expanded view event
     listen('menu-toggle-expanded', () => {
         console.log('Menu toggle expanded triggered');
@@ -836,7 +1175,6 @@ function setupContextMenuListeners() {
         return;
     }
 
-    const { listen } = window.__TAURI__.event;
 
     // Listen for project context menu data
     listen('show-project-context-menu', (event) => {
@@ -859,6 +1197,20 @@ function setupContextMenuListeners() {
         await showProjectSelector();
     });
 
+    // Keep the project menu in sync with the backend's catalog
+    listen('config-changed', (event) => {
+        console.log('Config file changed, applying new settings');
+        applyConfig(event.payload);
+        if (currentProjectData) {
+            scheduleRender();
+        }
+    });
+
+    listen('project-catalog-updated', (event) => {
+        console.log('Project catalog updated:', Object.keys(event.payload));
+        window.cachedProjectData = event.payload;
+    });
+
     // Listen for optimized project selector with pre-fetched data
     listen('show-project-context-menu-with-projects', async (event) => {
         console.log('Show project selector with pre-fetched data received');
@@ -1204,26 +1556,19 @@ async function showProjectSelector() {
 
     try {
         // Create a simple project selector dialog
-        const orgs = await invoke('list_organizations');
-        if (!orgs || orgs.length === 0) {
-            showError('No GitHub organizations found');
-            return;
-        }
+        const projectsByOrg = await invoke('project_catalog');
 
         // Build HTML for project selector
         let projectOptions = [];
-        for (const org of orgs) {
-            const projects = await invoke('list_org_projects', { org: org.login });
-            if (projects && projects.length > 0) {
-                projects.forEach(project => {
-                    projectOptions.push({
-                        id: project.id,
-                        name: `${org.login} / ${project.title}`,
-                        org: org.login,
-                        title: project.title
-                    });
+        for (const [orgLogin, projects] of Object.entries(projectsByOrg)) {
+            projects.forEach(project => {
+                projectOptions.push({
+                    id: project.id,
+                    name: `${orgLogin} / ${project.title}`,
+                    org: orgLogin,
+                    title: project.title
                 });
-            }
+            });
         }
 
         if (projectOptions.length === 0) {
@@ -1341,27 +1686,32 @@ async function showProjectMenuWithData(orgProjects) {
     }
 }
 
+// The window is told through 'my-items-changed', which applies the new filter
 async function toggleMyItems() {
     try {
-        showOnlyMyItems = await invoke('toggle_my_items');
-        console.log('My items filter toggled to:', showOnlyMyItems);
+        await invoke('toggle_my_items');
+    } catch (error) {
+        console.error('Failed to toggle my items filter:', error);
+    }
+}
 
-        // Get current username if we don't have it
-        if (showOnlyMyItems && !currentUsername) {
-            try {
-                currentUsername = await invoke('current_user');
-                console.log('Got current user:', currentUsername);
-            } catch (error) {
-                console.error('Failed to get current user:', error);
-                showError('Failed to get current GitHub user. Filter may not work correctly.');
-            }
-        }
+async function applyMyItemsFilter(enabled) {
+    showOnlyMyItems = enabled;
+    console.log('My items filter set to:', showOnlyMyItems);
 
-        if (currentProjectData) {
-            renderProject();
+    // Get current username if we don't have it
+    if (showOnlyMyItems && !currentUsername) {
+        try {
+            currentUsername = await invoke('current_user');
+            console.log('Got current user:', currentUsername);
+        } catch (error) {
+            console.error('Failed to get current user:', error);
+            showError('Failed to get current GitHub user. Filter may not work correctly.');
         }
-    } catch (error) {
-        console.error('Failed to toggle my items filter:', error);
+    }
+
+    if (currentProjectData) {
+        renderProject();
     }
 }
 
@@ -1482,12 +1832,12 @@ function setupWindowDragging() {
 }
 
 // Listen for project selection from Rust backend
-window.__TAURI__.event.listen('project-selected', async (event) => {
+listen('project-selected', async (event) => {
     await loadProjectData(event.payload.projectId);
 });
 
 // Listen for menu events
-window.__TAURI__.event.listen('menu-refresh', async () => {
+listen('menu-refresh', async () => {
     if (currentProjectData) {
         await loadProjectData(currentProjectData.project.id);
     } else {
@@ -1495,11 +1845,165 @@ window.__TAURI__.event.listen('menu-refresh', async () => {
     }
 });
 
-window.__TAURI__.event.listen('menu-toggle-expanded', async () => {
+listen('menu-toggle-expanded', async () => {
     await toggleView();
 });
 
-window.__TAURI__.event.listen('menu-select-project', async () => {
+listen('menu-select-project', async () => {
     // Show project selection dialog
     await showProjectSelector();
+});
+
+listen('menu-switch-project', async () => {
+    await openProjectSwitcher();
+});
+
+// Quick project switcher: fuzzy search with favorites and recent projects first
+let switcherMatches = [];
+let switcherSelection = 0;
+let switcherSearchTimer = null;
+
+async function openProjectSwitcher() {
+    const switcher = document.getElementById('project-switcher');
+    const input = document.getElementById('project-switcher-input');
+    if (!switcher.classList.contains('hidden')) {
+        input.focus();
+        return;
+    }
+
+    if (window.removeContextMenu) await window.removeContextMenu();
+
+    // Make sure the switcher fits, even in the minimized view
+    const width = Math.max(window.innerWidth, 400);
+    const height = Math.max(window.innerHeight, 360);
+    if (width !== window.innerWidth || height !== window.innerHeight) {
+        await invoke('resize_window_to_dimensions', { width, height });
+    }
+
+    switcher.classList.remove('hidden');
+    input.value = '';
+    input.focus();
+    await searchSwitcherProjects('');
+}
+
+function closeProjectSwitcher() {
+    const switcher = document.getElementById('project-switcher');
+    if (switcher.classList.contains('hidden')) return;
+
+    switcher.classList.add('hidden');
+    clearTimeout(switcherSearchTimer);
+    switcherMatches = [];
+
+    // Restore the window size of the current view
+    renderProject();
+}
+
+async function searchSwitcherProjects(query) {
+    try {
+        switcherMatches = await invoke('search_projects', { query });
+        switcherSelection = 0;
+        renderSwitcherResults();
+    } catch (error) {
+        console.error('Project search failed:', error);
+        document.getElementById('project-switcher-results').innerHTML =
+            '<div class="context-menu-error">Failed to load projects</div>';
+    }
+}
+
+function renderSwitcherResults() {
+    const results = document.getElementById('project-switcher-results');
+    results.innerHTML = '';
+
+    if (switcherMatches.length === 0) {
+        results.innerHTML = '<div class="context-menu-no-items">No matching projects</div>';
+        return;
+    }
+
+    switcherMatches.forEach((match, index) => {
+        const row = document.createElement('div');
+        row.className = 'project-switcher-item';
+        if (index === switcherSelection) row.classList.add('selected');
+        if (currentProjectData && currentProjectData.project.id === match.project.id) {
+            row.classList.add('current');
+        }
+
+        const star = document.createElement('span');
+        star.className = 'project-switcher-star' + (match.favorite ? ' favorite' : '');
+        star.textContent = match.favorite ? '★' : '☆';
+        star.title = match.favorite ? 'Remove from favorites' : 'Add to favorites';
+        star.addEventListener('mousedown', async (e) => {
+            e.preventDefault();
+            e.stopPropagation();
+            try {
+                await invoke('toggle_favorite_project', { projectId: match.project.id });
+                await searchSwitcherProjects(document.getElementById('project-switcher-input').value);
+            } catch (error) {
+                console.error('Failed to toggle favorite:', error);
+            }
+        });
+
+        const title = document.createElement('span');
+        title.className = 'project-switcher-title';
+        title.textContent = match.project.title;
+
+        const org = document.createElement('span');
+        org.className = 'project-switcher-org';
+        org.textContent = match.recent ? `${match.org} · recent` : match.org;
+
+        row.append(star, title, org);
+        row.addEventListener('mousedown', async (e) => {
+            e.preventDefault();
+            await switchToProject(match.project.id);
+        });
+        results.appendChild(row);
+    });
+
+    const selected = results.querySelector('.project-switcher-item.selected');
+    if (selected) selected.scrollIntoView({ block: 'nearest' });
+}
+
+async function switchToProject(projectId) {
+    closeProjectSwitcher();
+    try {
+        await invoke('select_project', { projectId });
+        await loadProjectData(projectId);
+    } catch (error) {
+        console.error('Failed to switch project:', error);
+        showError(`Failed to select project: ${error}`);
+    }
+}
+
+document.getElementById('project-switcher-input').addEventListener('input', (e) => {
+    clearTimeout(switcherSearchTimer);
+    const query = e.target.value;
+    switcherSearchTimer = setTimeout(() => searchSwitcherProjects(query), 80);
+});
+
+document.getElementById('project-switcher-input').addEventListener('keydown', async (e) => {
+    switch (e.key) {
+        case 'ArrowDown':
+            e.preventDefault();
+            switcherSelection = Math.min(switcherSelection + 1, switcherMatches.length - 1);
+            renderSwitcherResults();
+            break;
+        case 'ArrowUp':
+            e.preventDefault();
+            switcherSelection = Math.max(switcherSelection - 1, 0);
+            renderSwitcherResults();
+            break;
+        case 'Enter':
+            e.preventDefault();
+            if (switcherMatches[switcherSelection]) {
+                await switchToProject(switcherMatches[switcherSelection].project.id);
+            }
+            break;
+        case 'Escape':
+            e.preventDefault();
+            closeProjectSwitcher();
+            break;
+    }
+});
+
+document.getElementById('project-switcher-input').addEventListener('blur', () => {
+    closeProjectSwitcher();
 });
\ No newline at end of file